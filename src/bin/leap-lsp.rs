@@ -0,0 +1,8 @@
+use std::io;
+
+use leap_lang::lsp::server::Server;
+
+// language server over stdio
+fn main() -> io::Result<()> {
+    Server::new().run(io::stdin().lock(), io::stdout().lock())
+}
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+
+use crate::leaptypes::{
+    is_builtin_name, Attribute, LeapConst, LeapDefinition, LeapEnum, LeapSpec, LeapStruct,
+    LeapType, Literal, Name, Prop, SimpleType, ValueType,
+};
+use crate::parser::position::Position;
+use crate::rename::check_name;
+
+// builds checked spec without source, eg. in tests or importers from other formats:
+// `SpecBuilder::new().struct_("user", |s| s.prop("id", int())).build()`
+#[derive(Debug, Default)]
+pub struct SpecBuilder {
+    path: String,
+    definitions: Vec<LeapDefinition>,
+}
+
+#[derive(Debug)]
+pub struct StructBuilder {
+    leap_struct: LeapStruct,
+}
+
+#[derive(Debug)]
+pub struct EnumBuilder {
+    leap_enum: LeapEnum,
+}
+
+#[derive(Debug)]
+pub struct PropBuilder {
+    prop: Prop,
+}
+
+fn name(name: &str) -> Name {
+    // positions are unknown, names are checked in `SpecBuilder::build`
+    Name::new(name.to_owned(), Position::default()).unwrap()
+}
+
+fn attribute(attribute_name: &str, value: Option<&str>) -> Attribute {
+    Attribute {
+        name: attribute_name.to_owned(),
+        value: value.map(|v| v.to_owned()),
+        position: Position::default(),
+    }
+}
+
+pub fn simple(simple_type: SimpleType) -> ValueType {
+    ValueType::Simple(simple_type, Position::default())
+}
+
+pub fn string() -> ValueType {
+    simple(SimpleType::String)
+}
+
+pub fn int() -> ValueType {
+    simple(SimpleType::Integer)
+}
+
+pub fn float() -> ValueType {
+    simple(SimpleType::Float)
+}
+
+pub fn boolean() -> ValueType {
+    simple(SimpleType::Boolean)
+}
+
+pub fn list(element: ValueType) -> ValueType {
+    ValueType::List(Box::new(element), Position::default())
+}
+
+// reference to type or type argument, resolved in `SpecBuilder::build`
+pub fn named(type_name: &str) -> ValueType {
+    applied(type_name, vec![])
+}
+
+// reference to generic type with applied arguments
+pub fn applied(type_name: &str, args: Vec<ValueType>) -> ValueType {
+    ValueType::LeapType {
+        name: name(type_name),
+        args,
+        position: Position::default(),
+    }
+}
+
+impl SpecBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // path of the following definitions
+    pub fn path(mut self, path: &str) -> Self {
+        self.path = path.to_owned();
+        self
+    }
+
+    pub fn struct_(mut self, name: &str, f: impl FnOnce(StructBuilder) -> StructBuilder) -> Self {
+        let builder = f(StructBuilder {
+            leap_struct: LeapStruct {
+                name: self::name(name),
+                args: vec![],
+                props: vec![],
+                attributes: vec![],
+                doc: None,
+                path: self.path.clone(),
+                position: Position::default(),
+            },
+        });
+        self.definitions
+            .push(LeapDefinition::Type(LeapType::Struct(builder.leap_struct)));
+        self
+    }
+
+    pub fn enum_(mut self, name: &str, f: impl FnOnce(EnumBuilder) -> EnumBuilder) -> Self {
+        let builder = f(EnumBuilder {
+            leap_enum: LeapEnum {
+                name: self::name(name),
+                args: vec![],
+                variants: vec![],
+                attributes: vec![],
+                doc: None,
+                path: self.path.clone(),
+                position: Position::default(),
+            },
+        });
+        self.definitions
+            .push(LeapDefinition::Type(LeapType::Enum(builder.leap_enum)));
+        self
+    }
+
+    pub fn const_(mut self, name: &str, value_type: ValueType, value: Literal) -> Self {
+        self.definitions.push(LeapDefinition::Const(LeapConst {
+            name: self::name(name),
+            value_type,
+            value,
+            attributes: vec![],
+            doc: None,
+            path: self.path.clone(),
+            position: Position::default(),
+        }));
+        self
+    }
+
+    // checks names, resolves references to types and type arguments, checks defaults and
+    // constants, and marks recursive props
+    pub fn build(self) -> Result<LeapSpec, String> {
+        let mut arg_counts: HashMap<String, usize> = HashMap::new();
+        let mut const_names = vec![];
+        for definition in &self.definitions {
+            let name = definition.name().get();
+            check_name(name)?;
+            if is_builtin_name(name) {
+                return Err(format!("Builtin type `{}` can't be redefined", name));
+            }
+            match definition {
+                LeapDefinition::Type(t) => {
+                    if arg_counts.insert(name.to_owned(), t.args().len()).is_some() {
+                        return Err(format!("Type `{}` is defined more than once", name));
+                    }
+                }
+                LeapDefinition::Const(_) => {
+                    if const_names.contains(&name) {
+                        return Err(format!("Constant `{}` is defined more than once", name));
+                    }
+                    const_names.push(name);
+                }
+            }
+        }
+        if let Some(name) = const_names.iter().find(|n| arg_counts.contains_key(**n)) {
+            return Err(format!("Type and constant `{}` have the same name", name));
+        }
+        let mut definitions = vec![];
+        for mut definition in self.definitions {
+            match &mut definition {
+                LeapDefinition::Type(t) => {
+                    let args = t.args().to_vec();
+                    check_unique("Type argument", &args)?;
+                    let (kind, props) = match t {
+                        LeapType::Struct(s) => ("Prop", &mut s.props),
+                        LeapType::Enum(e) => ("Variant", &mut e.variants),
+                    };
+                    check_unique(
+                        kind,
+                        &props.iter().map(|p| p.name.clone()).collect::<Vec<_>>(),
+                    )?;
+                    for p in props {
+                        p.prop_type = resolve(&p.prop_type, &args, &arg_counts)?;
+                    }
+                }
+                LeapDefinition::Const(c) => {
+                    c.value_type = resolve(&c.value_type, &[], &arg_counts)?;
+                }
+            }
+            definitions.push(definition);
+        }
+        let mut spec = LeapSpec::from_definitions(definitions);
+        spec.check_defaults().map_err(|e| e.error)?;
+        spec.check_consts().map_err(|e| e.error)?;
+        spec.mark_recursive_props();
+        Ok(spec)
+    }
+}
+
+fn check_unique(kind: &str, names: &[Name]) -> Result<(), String> {
+    for (i, name) in names.iter().enumerate() {
+        check_name(name.get())?;
+        if names[..i].contains(name) {
+            return Err(format!("{} `{}` is defined more than once", kind, name));
+        }
+    }
+    Ok(())
+}
+
+fn resolve(
+    value_type: &ValueType,
+    type_args: &[Name],
+    arg_counts: &HashMap<String, usize>,
+) -> Result<ValueType, String> {
+    match value_type {
+        ValueType::Simple(..) => Ok(value_type.clone()),
+        ValueType::List(t, position) => Ok(ValueType::List(
+            Box::new(resolve(t, type_args, arg_counts)?),
+            *position,
+        )),
+        ValueType::TypeArg(name) => {
+            if type_args.contains(name) {
+                Ok(value_type.clone())
+            } else {
+                Err(format!("Unknown type argument `{}`", name))
+            }
+        }
+        ValueType::LeapType {
+            name,
+            args,
+            position,
+        } => {
+            if let Some(simple_type) = SimpleType::from_name(name.get()) {
+                return if args.is_empty() {
+                    Ok(ValueType::Simple(simple_type, *position))
+                } else {
+                    Err(format!("`{}` type should not have arguments", name))
+                };
+            }
+            if type_args.contains(name) {
+                return if args.is_empty() {
+                    Ok(ValueType::TypeArg(name.clone()))
+                } else {
+                    Err("Type argument can't have arguments".to_owned())
+                };
+            }
+            match arg_counts.get(name.get()) {
+                None => Err(format!("Unknown type `{}`", name)),
+                Some(count) if *count != args.len() => Err(format!(
+                    "Type `{}` expects {} arguments, got {}",
+                    name,
+                    count,
+                    args.len()
+                )),
+                Some(_) => Ok(ValueType::LeapType {
+                    name: name.clone(),
+                    args: args
+                        .iter()
+                        .map(|a| resolve(a, type_args, arg_counts))
+                        .collect::<Result<_, _>>()?,
+                    position: *position,
+                }),
+            }
+        }
+    }
+}
+
+impl StructBuilder {
+    pub fn arg(mut self, name: &str) -> Self {
+        self.leap_struct.args.push(self::name(name));
+        self
+    }
+
+    pub fn prop(self, name: &str, prop_type: ValueType) -> Self {
+        self.prop_with(name, prop_type, |p| p)
+    }
+
+    pub fn prop_with(
+        mut self,
+        name: &str,
+        prop_type: ValueType,
+        f: impl FnOnce(PropBuilder) -> PropBuilder,
+    ) -> Self {
+        self.leap_struct
+            .props
+            .push(PropBuilder::build(name, prop_type, f));
+        self
+    }
+
+    pub fn doc(mut self, doc: &str) -> Self {
+        self.leap_struct.doc = Some(doc.to_owned());
+        self
+    }
+
+    pub fn attribute(mut self, name: &str, value: Option<&str>) -> Self {
+        self.leap_struct.attributes.push(attribute(name, value));
+        self
+    }
+}
+
+impl EnumBuilder {
+    pub fn arg(mut self, name: &str) -> Self {
+        self.leap_enum.args.push(self::name(name));
+        self
+    }
+
+    pub fn variant(self, name: &str, variant_type: ValueType) -> Self {
+        self.variant_with(name, variant_type, |v| v)
+    }
+
+    pub fn variant_with(
+        mut self,
+        name: &str,
+        variant_type: ValueType,
+        f: impl FnOnce(PropBuilder) -> PropBuilder,
+    ) -> Self {
+        self.leap_enum
+            .variants
+            .push(PropBuilder::build(name, variant_type, f));
+        self
+    }
+
+    pub fn doc(mut self, doc: &str) -> Self {
+        self.leap_enum.doc = Some(doc.to_owned());
+        self
+    }
+
+    pub fn attribute(mut self, name: &str, value: Option<&str>) -> Self {
+        self.leap_enum.attributes.push(attribute(name, value));
+        self
+    }
+}
+
+impl PropBuilder {
+    fn build(name: &str, prop_type: ValueType, f: impl FnOnce(Self) -> Self) -> Prop {
+        f(PropBuilder {
+            prop: Prop {
+                name: self::name(name),
+                prop_type,
+                default: None,
+                attributes: vec![],
+                doc: None,
+                position: Position::default(),
+                // marked in `SpecBuilder::build`
+                is_recursive: false,
+            },
+        })
+        .prop
+    }
+
+    pub fn default(mut self, value: Literal) -> Self {
+        self.prop.default = Some(value);
+        self
+    }
+
+    pub fn doc(mut self, doc: &str) -> Self {
+        self.prop.doc = Some(doc.to_owned());
+        self
+    }
+
+    pub fn attribute(mut self, name: &str, value: Option<&str>) -> Self {
+        self.prop.attributes.push(attribute(name, value));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter;
+
+    #[test]
+    fn test_build() {
+        let spec = SpecBuilder::new()
+            .path("a.leap")
+            .struct_("user", |s| {
+                s.doc("user of the service")
+                    .prop("id", int())
+                    .prop_with("name", string(), |p| {
+                        p.default(Literal::String("guest".to_owned()))
+                    })
+                    .prop("role", named("role"))
+                    .prop("friends", list(named("user")))
+            })
+            .enum_("role", |e| {
+                e.variant("admin", named("admin"))
+                    .variant("guest", boolean())
+            })
+            .struct_("admin", |s| {
+                s.attribute("since", Some("2")).prop("user", named("user"))
+            })
+            .struct_("page", |s| s.arg("t").prop("items", list(named("t"))))
+            .const_("max-users", int(), Literal::Integer(10))
+            .build()
+            .unwrap();
+        assert_eq!(
+            formatter::print(&spec),
+            "/-- a.leap\n\n/-- user of the service\n.struct user\n    id: int\n    name: str = \"guest\"\n    role: role\n    friends: list[user]\n\n.enum role\n    admin\n    guest: bool\n\n@since 2\n.struct admin\n    user: user\n\n.struct page[t]\n    items: list[t]\n\n.const max-users: int = 10\n"
+        );
+        let page = spec.get_type_by_name("page").unwrap().as_struct().unwrap();
+        assert!(matches!(
+            page.props[0].prop_type,
+            ValueType::List(ref t, _) if matches!(**t, ValueType::TypeArg(_))
+        ));
+        let user = spec.get_type_by_name("user").unwrap().as_struct().unwrap();
+        // recursive through `role` and `admin`, list is not counted
+        assert!(user.props[2].is_recursive);
+        assert!(!user.props[3].is_recursive);
+    }
+
+    #[test]
+    fn test_build_errors() {
+        let error = |builder: SpecBuilder| builder.build().unwrap_err();
+        assert_eq!(
+            error(SpecBuilder::new().struct_("User", |s| s)),
+            "Invalid name `User`"
+        );
+        assert_eq!(
+            error(SpecBuilder::new().struct_("a", |s| s).enum_("a", |e| e)),
+            "Type `a` is defined more than once"
+        );
+        assert_eq!(
+            error(SpecBuilder::new().struct_("date", |s| s)),
+            "Builtin type `date` can't be redefined"
+        );
+        assert_eq!(
+            error(SpecBuilder::new().const_("list", int(), Literal::Integer(1))),
+            "Builtin type `list` can't be redefined"
+        );
+        assert_eq!(
+            error(
+                SpecBuilder::new()
+                    .const_("a", int(), Literal::Integer(1))
+                    .struct_("a", |s| s)
+            ),
+            "Type and constant `a` have the same name"
+        );
+        assert_eq!(
+            error(SpecBuilder::new().struct_("a", |s| s.prop("x", int()).prop("x", int()))),
+            "Prop `x` is defined more than once"
+        );
+        assert_eq!(
+            error(SpecBuilder::new().struct_("a", |s| s.prop("x", named("b")))),
+            "Unknown type `b`"
+        );
+        assert_eq!(
+            error(
+                SpecBuilder::new()
+                    .struct_("a", |s| s.arg("t"))
+                    .struct_("b", |s| s.prop("x", named("a")))
+            ),
+            "Type `a` expects 1 arguments, got 0"
+        );
+        assert_eq!(
+            error(SpecBuilder::new().struct_("a", |s| {
+                s.prop_with("x", int(), |p| p.default(Literal::Boolean(true)))
+            })),
+            "Value `true` can't be used for type `int`"
+        );
+    }
+}
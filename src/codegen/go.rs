@@ -0,0 +1,1183 @@
+use super::doc_lines;
+use crate::leaptypes::{
+    LeapConst, LeapEnum, LeapSpec, LeapStruct, LeapType, Name, Prop, SimpleType, ValueType,
+};
+use crate::naming::{self, WritingStyle};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoOptions {
+    pub package: String,
+    // field with variant name in enum values, fields of struct variants are next to it,
+    // other values are in `value` field
+    pub enum_tag: String,
+    // use type parameters for type args, otherwise a separate type is generated for every
+    // applied generic type, eg. `OptionUser` for `option[user]`
+    pub generics: bool,
+}
+
+impl Default for GoOptions {
+    fn default() -> Self {
+        GoOptions {
+            package: "models".to_owned(),
+            enum_tag: "type".to_owned(),
+            generics: true,
+        }
+    }
+}
+
+struct GoType {
+    // with applied type args in monomorphized mode
+    leap_type: LeapType,
+    // applied type args, empty when type parameters are used
+    applied: Vec<ValueType>,
+}
+
+// `bytes` is `[]byte` (base64 in json), `decimal`, `date`, `datetime`, `duration` and `uuid`
+// are strings
+pub fn generate(spec: &LeapSpec, options: &GoOptions) -> String {
+    let go_types = if options.generics {
+        spec.iter_type_refs()
+            .map(|t| GoType {
+                leap_type: t.clone(),
+                applied: vec![],
+            })
+            .collect()
+    } else {
+        monomorphize(spec)
+    };
+    let mut items = vec![format!("package {}\n", options.package)];
+    if go_types.iter().any(|t| t.leap_type.is_enum()) {
+        items.push("import (\n\t\"encoding/json\"\n\t\"fmt\"\n)\n".to_owned());
+    }
+    let type_names: Vec<String> = go_types.iter().map(go_type_name).collect();
+    // names of all declared types, constants can't reuse them
+    let mut taken = type_names.clone();
+    for (go_type, name) in go_types.iter().zip(&type_names) {
+        match &go_type.leap_type {
+            LeapType::Struct(s) => items.push(generate_struct(s, name, options)),
+            LeapType::Enum(e) => {
+                let variant_names = variant_names(e, name, &type_names);
+                items.extend(generate_enum(spec, e, name, &variant_names, options));
+                taken.push(format!("{}Variant", name));
+                taken.extend(variant_names);
+            }
+        }
+    }
+    items.extend(spec.iter_consts().filter_map(|c| generate_const(c, &taken)));
+    items.join("\n")
+}
+
+// generic types are applied to all type args used by non generic types, unused generic types
+// are skipped
+fn monomorphize(spec: &LeapSpec) -> Vec<GoType> {
+    let mut go_types: Vec<GoType> = spec
+        .iter_type_refs()
+        .filter(|t| t.args().is_empty())
+        .map(|t| GoType {
+            leap_type: t.clone(),
+            applied: vec![],
+        })
+        .collect();
+    let mut i = 0;
+    while i < go_types.len() {
+        let mut applied = vec![];
+        for prop in props(&go_types[i].leap_type) {
+            collect_applied(&prop.prop_type, &mut applied);
+        }
+        for (name, args) in applied {
+            let is_new = !go_types
+                .iter()
+                .any(|t| t.leap_type.name().get() == name.get() && t.applied == args);
+            if let (true, Some(leap_type)) = (is_new, spec.get_type_by_name(name.get())) {
+                go_types.push(GoType {
+                    leap_type: leap_type.apply_args(&args),
+                    applied: args,
+                });
+            }
+        }
+        i += 1;
+    }
+    go_types
+}
+
+fn collect_applied(value_type: &ValueType, applied: &mut Vec<(Name, Vec<ValueType>)>) {
+    match value_type {
+        ValueType::Simple(..) | ValueType::TypeArg(_) => {}
+        ValueType::List(t, _) => collect_applied(t, applied),
+        ValueType::LeapType { name, args, .. } => {
+            if !args.is_empty() {
+                applied.push((name.clone(), args.clone()));
+            }
+            for arg in args {
+                collect_applied(arg, applied);
+            }
+        }
+    }
+}
+
+fn props(leap_type: &LeapType) -> &[Prop] {
+    match leap_type {
+        LeapType::Struct(s) => &s.props,
+        LeapType::Enum(e) => &e.variants,
+    }
+}
+
+fn generate_struct(leap_struct: &LeapStruct, name: &str, options: &GoOptions) -> String {
+    let mut text = doc_lines(leap_struct.doc.as_deref(), "", "// ");
+    text.push_str(&format!(
+        "type {}{} struct",
+        name,
+        type_params(&leap_struct.args)
+    ));
+    if leap_struct.props.is_empty() {
+        text.push_str("{}\n");
+        return text;
+    }
+    text.push_str(" {\n");
+    for prop in &leap_struct.props {
+        text.push_str(&doc_lines(prop.doc.as_deref(), "\t", "// "));
+        // recursive props are pointers to have a known size
+        let pointer = if prop.is_recursive { "*" } else { "" };
+        text.push_str(&format!(
+            "\t{} {}{} `json:\"{}\"`\n",
+            field_name(&prop.name),
+            pointer,
+            go_type(&prop.prop_type, options),
+            prop.name.get()
+        ));
+    }
+    text.push_str("}\n");
+    text
+}
+
+// variant structs are named by enum and variant, eg. `RoleAdmin`, with `Variant` suffix if the
+// name is taken by a type or by the variant interface
+fn variant_names(leap_enum: &LeapEnum, name: &str, type_names: &[String]) -> Vec<String> {
+    let interface = format!("{}Variant", name);
+    leap_enum
+        .variants
+        .iter()
+        .map(|v| {
+            let variant_name = format!("{}{}", name, type_name(&v.name));
+            if variant_name == interface || type_names.contains(&variant_name) {
+                format!("{}Variant", variant_name)
+            } else {
+                variant_name
+            }
+        })
+        .collect()
+}
+
+// enum is a struct with a value of sealed interface implemented by variants, it (un)marshals
+// variants with the tag field, variants embed struct values and keep other values in `Value`
+fn generate_enum(
+    spec: &LeapSpec,
+    leap_enum: &LeapEnum,
+    name: &str,
+    variant_names: &[String],
+    options: &GoOptions,
+) -> Vec<String> {
+    let params = type_params(&leap_enum.args);
+    let args = type_args(&leap_enum.args);
+    let mut items = vec![];
+    let mut text = doc_lines(leap_enum.doc.as_deref(), "", "// ");
+    text.push_str(&format!(
+        "type {}{} struct {{\n\tVariant {}Variant\n}}\n",
+        name, params, name
+    ));
+    items.push(text);
+    items.push(format!(
+        "// {}Variant is implemented by variants of {}\ntype {}Variant interface {{\n\tis{}()\n}}\n",
+        name, name, name, name
+    ));
+    for (variant, variant_name) in leap_enum.variants.iter().zip(variant_names) {
+        let mut text = doc_lines(variant.doc.as_deref(), "", "// ");
+        let is_struct = matches!(&variant.prop_type, ValueType::LeapType { name, .. }
+            if spec.get_type_by_name(name.get()).is_some_and(|t| t.is_struct()));
+        let field = if is_struct {
+            go_type(&variant.prop_type, options)
+        } else {
+            format!(
+                "Value {} `json:\"value\"`",
+                go_type(&variant.prop_type, options)
+            )
+        };
+        text.push_str(&format!(
+            "type {}{} struct {{\n\t{}\n}}\n\nfunc ({}{}) is{}() {{}}\n",
+            variant_name, params, field, variant_name, args, name
+        ));
+        items.push(text);
+    }
+    // switch with unused variable doesn't compile, so it's omitted for empty enums
+    let switch = if leap_enum.variants.is_empty() {
+        ""
+    } else {
+        "\tswitch variant := v.Variant.(type) {\n"
+    };
+    let mut text = format!(
+        "func (v {}{}) MarshalJSON() ([]byte, error) {{\n{}",
+        name, args, switch
+    );
+    for (variant, variant_name) in leap_enum.variants.iter().zip(variant_names) {
+        text.push_str(&format!(
+            "\tcase {}{}:\n\t\treturn json.Marshal(struct {{\n\t\t\tTag string `json:\"{}\"`\n\t\t\t{}{}\n\t\t}}{{\"{}\", variant}})\n",
+            variant_name,
+            args,
+            options.enum_tag,
+            variant_name,
+            args,
+            variant.name.get()
+        ));
+    }
+    if !leap_enum.variants.is_empty() {
+        text.push_str("\t}\n");
+    }
+    text.push_str(&format!(
+        "\treturn nil, fmt.Errorf(\"unknown variant %T of {}\", v.Variant)\n}}\n",
+        name
+    ));
+    items.push(text);
+    let mut text = format!(
+        "func (v *{}{}) UnmarshalJSON(data []byte) error {{\n\tvar tag struct {{\n\t\tTag string `json:\"{}\"`\n\t}}\n\tif err := json.Unmarshal(data, &tag); err != nil {{\n\t\treturn err\n\t}}\n\tswitch tag.Tag {{\n",
+        name, args, options.enum_tag
+    );
+    for (variant, variant_name) in leap_enum.variants.iter().zip(variant_names) {
+        text.push_str(&format!(
+            "\tcase \"{}\":\n\t\tvar variant {}{}\n\t\terr := json.Unmarshal(data, &variant)\n\t\tv.Variant = variant\n\t\treturn err\n",
+            variant.name.get(),
+            variant_name,
+            args
+        ));
+    }
+    text.push_str(&format!(
+        "\t}}\n\treturn fmt.Errorf(\"unknown variant %q of {}\", tag.Tag)\n}}\n",
+        name
+    ));
+    items.push(text);
+    items
+}
+
+// only constants of simple types are generated, names taken by types get `Const` suffix
+fn generate_const(leap_const: &LeapConst, taken: &[String]) -> Option<String> {
+    let const_type = match &leap_const.value_type {
+        ValueType::Simple(SimpleType::Bytes, _) => return None,
+        ValueType::Simple(t, _) => simple_type(t),
+        _ => return None,
+    };
+    let mut name = type_name(&leap_const.name);
+    if taken.contains(&name) {
+        name.push_str("Const");
+    }
+    let mut text = doc_lines(leap_const.doc.as_deref(), "", "// ");
+    text.push_str(&format!(
+        "const {} {} = {}\n",
+        name, const_type, leap_const.value
+    ));
+    Some(text)
+}
+
+fn go_type_name(go_type: &GoType) -> String {
+    let name = type_name(go_type.leap_type.name());
+    go_type
+        .applied
+        .iter()
+        .fold(name, |name, a| name + &name_part(a))
+}
+
+// part of the name of monomorphized type, eg. `ListUser` in `PageListUser`
+fn name_part(value_type: &ValueType) -> String {
+    match value_type {
+        ValueType::Simple(t, _) => {
+            naming::apply_style(WritingStyle::UpperCamel, "", &naming::get_parts(t.as_str()))
+        }
+        ValueType::List(t, _) => format!("List{}", name_part(t)),
+        ValueType::TypeArg(name) => type_name(name),
+        ValueType::LeapType { name, args, .. } => args
+            .iter()
+            .fold(type_name(name), |name, a| name + &name_part(a)),
+    }
+}
+
+fn type_name(name: &Name) -> String {
+    name.apply_style(WritingStyle::UpperCamel, "")
+}
+
+// exported field
+fn field_name(name: &Name) -> String {
+    name.apply_style(WritingStyle::UpperCamel, "")
+}
+
+fn type_params(args: &[Name]) -> String {
+    if args.is_empty() {
+        "".to_owned()
+    } else {
+        let args: Vec<String> = args.iter().map(type_name).collect();
+        format!("[{} any]", args.join(", "))
+    }
+}
+
+fn type_args(args: &[Name]) -> String {
+    if args.is_empty() {
+        "".to_owned()
+    } else {
+        let args: Vec<String> = args.iter().map(type_name).collect();
+        format!("[{}]", args.join(", "))
+    }
+}
+
+fn go_type(value_type: &ValueType, options: &GoOptions) -> String {
+    match value_type {
+        ValueType::Simple(t, _) => simple_type(t).to_owned(),
+        ValueType::List(t, _) => format!("[]{}", go_type(t, options)),
+        ValueType::TypeArg(name) => type_name(name),
+        ValueType::LeapType { args, .. } if !options.generics || args.is_empty() => {
+            name_part(value_type)
+        }
+        ValueType::LeapType { name, args, .. } => {
+            let args: Vec<String> = args.iter().map(|a| go_type(a, options)).collect();
+            format!("{}[{}]", type_name(name), args.join(", "))
+        }
+    }
+}
+
+fn simple_type(simple_type: &SimpleType) -> &'static str {
+    match simple_type {
+        SimpleType::Integer => "int64",
+        SimpleType::Float => "float64",
+        SimpleType::Boolean => "bool",
+        SimpleType::Int8 => "int8",
+        SimpleType::Int16 => "int16",
+        SimpleType::Int32 => "int32",
+        SimpleType::Int64 => "int64",
+        SimpleType::UInt8 => "uint8",
+        SimpleType::UInt16 => "uint16",
+        SimpleType::UInt32 => "uint32",
+        SimpleType::UInt64 => "uint64",
+        SimpleType::Bytes => "[]byte",
+        SimpleType::String
+        | SimpleType::Decimal
+        | SimpleType::Date
+        | SimpleType::DateTime
+        | SimpleType::Duration
+        | SimpleType::Uuid => "string",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    const DATA: &str = "
+        /-- user of the service
+        .struct user
+            id: int
+            first-name: str /-- given name
+            roles: list[role]
+            manager: option[user]
+
+        .struct page[t]
+            items: list[t]
+
+        .struct none
+        .struct some[t]
+            value: t
+        /-- optional value
+        .enum option[t]
+            none
+            some[t]
+
+        .enum role
+            admin: user
+            guest: none
+
+        .struct users
+            page: page[user]
+
+        .enum empty
+
+        .const max-page-size: int = 100
+        .const api-version: str = \"2.3\"
+    ";
+
+    #[test]
+    fn test_generics() {
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(DATA).unwrap());
+        spec.mark_recursive_props();
+        assert_eq!(
+            generate(&spec, &GoOptions::default()),
+            r#"package models
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+// user of the service
+type User struct {
+	Id int64 `json:"id"`
+	// given name
+	FirstName string `json:"first-name"`
+	Roles []Role `json:"roles"`
+	Manager *Option[User] `json:"manager"`
+}
+
+type Page[T any] struct {
+	Items []T `json:"items"`
+}
+
+type None struct{}
+
+type Some[T any] struct {
+	Value T `json:"value"`
+}
+
+// optional value
+type Option[T any] struct {
+	Variant OptionVariant
+}
+
+// OptionVariant is implemented by variants of Option
+type OptionVariant interface {
+	isOption()
+}
+
+type OptionNone[T any] struct {
+	None
+}
+
+func (OptionNone[T]) isOption() {}
+
+type OptionSome[T any] struct {
+	Some[T]
+}
+
+func (OptionSome[T]) isOption() {}
+
+func (v Option[T]) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case OptionNone[T]:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionNone[T]
+		}{"none", variant})
+	case OptionSome[T]:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionSome[T]
+		}{"some", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of Option", v.Variant)
+}
+
+func (v *Option[T]) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "none":
+		var variant OptionNone[T]
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "some":
+		var variant OptionSome[T]
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of Option", tag.Tag)
+}
+
+type Role struct {
+	Variant RoleVariant
+}
+
+// RoleVariant is implemented by variants of Role
+type RoleVariant interface {
+	isRole()
+}
+
+type RoleAdmin struct {
+	User
+}
+
+func (RoleAdmin) isRole() {}
+
+type RoleGuest struct {
+	None
+}
+
+func (RoleGuest) isRole() {}
+
+func (v Role) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case RoleAdmin:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			RoleAdmin
+		}{"admin", variant})
+	case RoleGuest:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			RoleGuest
+		}{"guest", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of Role", v.Variant)
+}
+
+func (v *Role) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "admin":
+		var variant RoleAdmin
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "guest":
+		var variant RoleGuest
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of Role", tag.Tag)
+}
+
+type Users struct {
+	Page Page[User] `json:"page"`
+}
+
+type Empty struct {
+	Variant EmptyVariant
+}
+
+// EmptyVariant is implemented by variants of Empty
+type EmptyVariant interface {
+	isEmpty()
+}
+
+func (v Empty) MarshalJSON() ([]byte, error) {
+	return nil, fmt.Errorf("unknown variant %T of Empty", v.Variant)
+}
+
+func (v *Empty) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	}
+	return fmt.Errorf("unknown variant %q of Empty", tag.Tag)
+}
+
+const MaxPageSize int64 = 100
+
+const ApiVersion string = "2.3"
+"#
+        );
+    }
+
+    #[test]
+    fn test_monomorphized() {
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(DATA).unwrap());
+        spec.mark_recursive_props();
+        let options = GoOptions {
+            generics: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            generate(&spec, &options),
+            r#"package models
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+// user of the service
+type User struct {
+	Id int64 `json:"id"`
+	// given name
+	FirstName string `json:"first-name"`
+	Roles []Role `json:"roles"`
+	Manager *OptionUser `json:"manager"`
+}
+
+type None struct{}
+
+type Role struct {
+	Variant RoleVariant
+}
+
+// RoleVariant is implemented by variants of Role
+type RoleVariant interface {
+	isRole()
+}
+
+type RoleAdmin struct {
+	User
+}
+
+func (RoleAdmin) isRole() {}
+
+type RoleGuest struct {
+	None
+}
+
+func (RoleGuest) isRole() {}
+
+func (v Role) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case RoleAdmin:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			RoleAdmin
+		}{"admin", variant})
+	case RoleGuest:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			RoleGuest
+		}{"guest", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of Role", v.Variant)
+}
+
+func (v *Role) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "admin":
+		var variant RoleAdmin
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "guest":
+		var variant RoleGuest
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of Role", tag.Tag)
+}
+
+type Users struct {
+	Page PageUser `json:"page"`
+}
+
+type Empty struct {
+	Variant EmptyVariant
+}
+
+// EmptyVariant is implemented by variants of Empty
+type EmptyVariant interface {
+	isEmpty()
+}
+
+func (v Empty) MarshalJSON() ([]byte, error) {
+	return nil, fmt.Errorf("unknown variant %T of Empty", v.Variant)
+}
+
+func (v *Empty) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	}
+	return fmt.Errorf("unknown variant %q of Empty", tag.Tag)
+}
+
+// optional value
+type OptionUser struct {
+	Variant OptionUserVariant
+}
+
+// OptionUserVariant is implemented by variants of OptionUser
+type OptionUserVariant interface {
+	isOptionUser()
+}
+
+type OptionUserNone struct {
+	None
+}
+
+func (OptionUserNone) isOptionUser() {}
+
+type OptionUserSome struct {
+	SomeUser
+}
+
+func (OptionUserSome) isOptionUser() {}
+
+func (v OptionUser) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case OptionUserNone:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionUserNone
+		}{"none", variant})
+	case OptionUserSome:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionUserSome
+		}{"some", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of OptionUser", v.Variant)
+}
+
+func (v *OptionUser) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "none":
+		var variant OptionUserNone
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "some":
+		var variant OptionUserSome
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of OptionUser", tag.Tag)
+}
+
+type PageUser struct {
+	Items []User `json:"items"`
+}
+
+type SomeUser struct {
+	Value User `json:"value"`
+}
+
+const MaxPageSize int64 = 100
+
+const ApiVersion string = "2.3"
+"#
+        );
+    }
+    #[test]
+    fn test_edge_cases() {
+        // variants with the same type, names which are go keywords or taken by types
+        let data = "
+            .struct user
+                type: str
+                func: list[user]
+            .struct role-admin
+            .enum role
+                admin: user
+                owner: user
+                variant: role-admin
+            .const ratio: float = 1
+            .const user: bool = false
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &GoOptions::default()),
+            r#"package models
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+type User struct {
+	Type string `json:"type"`
+	Func []User `json:"func"`
+}
+
+type RoleAdmin struct{}
+
+type Role struct {
+	Variant RoleVariant
+}
+
+// RoleVariant is implemented by variants of Role
+type RoleVariant interface {
+	isRole()
+}
+
+type RoleAdminVariant struct {
+	User
+}
+
+func (RoleAdminVariant) isRole() {}
+
+type RoleOwner struct {
+	User
+}
+
+func (RoleOwner) isRole() {}
+
+type RoleVariantVariant struct {
+	RoleAdmin
+}
+
+func (RoleVariantVariant) isRole() {}
+
+func (v Role) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case RoleAdminVariant:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			RoleAdminVariant
+		}{"admin", variant})
+	case RoleOwner:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			RoleOwner
+		}{"owner", variant})
+	case RoleVariantVariant:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			RoleVariantVariant
+		}{"variant", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of Role", v.Variant)
+}
+
+func (v *Role) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "admin":
+		var variant RoleAdminVariant
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "owner":
+		var variant RoleOwner
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "variant":
+		var variant RoleVariantVariant
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of Role", tag.Tag)
+}
+
+const Ratio float64 = 1
+
+const UserConst bool = false
+"#
+        );
+    }
+
+    #[test]
+    fn test_value_variants() {
+        // values of variants which are not structs are in `Value` field
+        let data = "
+            .struct empty
+            .enum option[t]
+                some: t
+                none: empty
+            .enum tree
+                leaf: int
+                branch: list[tree]
+            .struct holder
+                value: option[str]
+        ";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        spec.mark_recursive_props();
+        assert_eq!(
+            generate(&spec, &GoOptions::default()),
+            r#"package models
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+type Empty struct{}
+
+type Option[T any] struct {
+	Variant OptionVariant
+}
+
+// OptionVariant is implemented by variants of Option
+type OptionVariant interface {
+	isOption()
+}
+
+type OptionSome[T any] struct {
+	Value T `json:"value"`
+}
+
+func (OptionSome[T]) isOption() {}
+
+type OptionNone[T any] struct {
+	Empty
+}
+
+func (OptionNone[T]) isOption() {}
+
+func (v Option[T]) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case OptionSome[T]:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionSome[T]
+		}{"some", variant})
+	case OptionNone[T]:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionNone[T]
+		}{"none", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of Option", v.Variant)
+}
+
+func (v *Option[T]) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "some":
+		var variant OptionSome[T]
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "none":
+		var variant OptionNone[T]
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of Option", tag.Tag)
+}
+
+type Tree struct {
+	Variant TreeVariant
+}
+
+// TreeVariant is implemented by variants of Tree
+type TreeVariant interface {
+	isTree()
+}
+
+type TreeLeaf struct {
+	Value int64 `json:"value"`
+}
+
+func (TreeLeaf) isTree() {}
+
+type TreeBranch struct {
+	Value []Tree `json:"value"`
+}
+
+func (TreeBranch) isTree() {}
+
+func (v Tree) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case TreeLeaf:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			TreeLeaf
+		}{"leaf", variant})
+	case TreeBranch:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			TreeBranch
+		}{"branch", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of Tree", v.Variant)
+}
+
+func (v *Tree) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "leaf":
+		var variant TreeLeaf
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "branch":
+		var variant TreeBranch
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of Tree", tag.Tag)
+}
+
+type Holder struct {
+	Value Option[string] `json:"value"`
+}
+"#
+        );
+        let options = GoOptions {
+            generics: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            generate(&spec, &options),
+            r#"package models
+
+import (
+	"encoding/json"
+	"fmt"
+)
+
+type Empty struct{}
+
+type Tree struct {
+	Variant TreeVariant
+}
+
+// TreeVariant is implemented by variants of Tree
+type TreeVariant interface {
+	isTree()
+}
+
+type TreeLeaf struct {
+	Value int64 `json:"value"`
+}
+
+func (TreeLeaf) isTree() {}
+
+type TreeBranch struct {
+	Value []Tree `json:"value"`
+}
+
+func (TreeBranch) isTree() {}
+
+func (v Tree) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case TreeLeaf:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			TreeLeaf
+		}{"leaf", variant})
+	case TreeBranch:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			TreeBranch
+		}{"branch", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of Tree", v.Variant)
+}
+
+func (v *Tree) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "leaf":
+		var variant TreeLeaf
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "branch":
+		var variant TreeBranch
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of Tree", tag.Tag)
+}
+
+type Holder struct {
+	Value OptionStr `json:"value"`
+}
+
+type OptionStr struct {
+	Variant OptionStrVariant
+}
+
+// OptionStrVariant is implemented by variants of OptionStr
+type OptionStrVariant interface {
+	isOptionStr()
+}
+
+type OptionStrSome struct {
+	Value string `json:"value"`
+}
+
+func (OptionStrSome) isOptionStr() {}
+
+type OptionStrNone struct {
+	Empty
+}
+
+func (OptionStrNone) isOptionStr() {}
+
+func (v OptionStr) MarshalJSON() ([]byte, error) {
+	switch variant := v.Variant.(type) {
+	case OptionStrSome:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionStrSome
+		}{"some", variant})
+	case OptionStrNone:
+		return json.Marshal(struct {
+			Tag string `json:"type"`
+			OptionStrNone
+		}{"none", variant})
+	}
+	return nil, fmt.Errorf("unknown variant %T of OptionStr", v.Variant)
+}
+
+func (v *OptionStr) UnmarshalJSON(data []byte) error {
+	var tag struct {
+		Tag string `json:"type"`
+	}
+	if err := json.Unmarshal(data, &tag); err != nil {
+		return err
+	}
+	switch tag.Tag {
+	case "some":
+		var variant OptionStrSome
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	case "none":
+		var variant OptionStrNone
+		err := json.Unmarshal(data, &variant)
+		v.Variant = variant
+		return err
+	}
+	return fmt.Errorf("unknown variant %q of OptionStr", tag.Tag)
+}
+"#
+        );
+    }
+}
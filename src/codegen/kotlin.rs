@@ -0,0 +1,636 @@
+use super::doc_lines;
+use super::lowering::{self, ConstDecl, Field, Record, TypeDecl, TypeRef, Union};
+use crate::leaptypes::{LeapSpec, Literal, Name, SimpleType};
+use crate::naming::WritingStyle;
+use crate::parser::position::Position;
+
+const INDENT: &str = "    ";
+
+const KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "class",
+    "continue",
+    "do",
+    "else",
+    "false",
+    "for",
+    "fun",
+    "if",
+    "in",
+    "interface",
+    "is",
+    "null",
+    "object",
+    "package",
+    "return",
+    "super",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typealias",
+    "typeof",
+    "val",
+    "var",
+    "when",
+    "while",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct KotlinOptions {
+    pub package: String,
+    // class discriminator of sealed classes, fields of struct variants are next to it,
+    // other values are in `value` field
+    pub enum_tag: String,
+}
+
+// names declared where a type is referenced, top level types hidden by nested variant classes
+// are qualified with the package and hidden kotlin types with their package
+struct Scope<'a> {
+    package: &'a str,
+    types: &'a [String],
+    variants: Vec<String>,
+}
+
+impl Default for KotlinOptions {
+    fn default() -> Self {
+        KotlinOptions {
+            package: "models".to_owned(),
+            enum_tag: "type".to_owned(),
+        }
+    }
+}
+
+// structs are data classes and enums are sealed classes with a subclass for every variant which
+// has fields of the variant struct, so values are serialized by kotlinx.serialization with the
+// class discriminator next to the fields, other variants have a `value` field; `bytes`, `decimal`, `date`, `datetime`, `duration`
+// and `uuid` are strings
+pub fn generate(spec: &LeapSpec, options: &KotlinOptions) -> String {
+    let module = lowering::lower(spec);
+    let mut imports = vec![
+        "kotlinx.serialization.SerialName",
+        "kotlinx.serialization.Serializable",
+    ];
+    // `type` is the default class discriminator
+    if options.enum_tag != "type" {
+        imports.extend([
+            "kotlinx.serialization.ExperimentalSerializationApi",
+            "kotlinx.serialization.json.JsonClassDiscriminator",
+        ]);
+    }
+    imports.sort();
+    let mut header = format!("package {}\n\n", options.package);
+    for import in imports {
+        header.push_str(&format!("import {}\n", import));
+    }
+    let mut items = vec![header];
+    let types: Vec<String> = module
+        .types
+        .iter()
+        .map(|t| match t {
+            TypeDecl::Record(r) => type_name(&r.name),
+            TypeDecl::Union(u) => type_name(&u.name),
+        })
+        .collect();
+    for type_decl in &module.types {
+        items.push(match type_decl {
+            TypeDecl::Record(r) => generate_class(r, options, &types),
+            TypeDecl::Union(u) => generate_sealed_class(u, options, &types),
+        });
+    }
+    let scope = Scope {
+        package: &options.package,
+        types: &types,
+        variants: vec![],
+    };
+    items.extend(module.consts.iter().map(|c| generate_const(c, &scope)));
+    items.join("\n")
+}
+
+fn generate_class(record: &Record, options: &KotlinOptions, types: &[String]) -> String {
+    let scope = Scope {
+        package: &options.package,
+        types,
+        variants: vec![],
+    };
+    let mut text = kdoc(record.doc.as_deref(), "");
+    text.push_str("@Serializable\n");
+    text.push_str(&class_header(
+        &type_name(&record.name),
+        &type_params(&record.params),
+        &record.fields,
+        "",
+    ));
+    text.push_str(&class_fields(&record.fields, "", &scope));
+    text
+}
+
+fn generate_sealed_class(union: &Union, options: &KotlinOptions, types: &[String]) -> String {
+    let scope = Scope {
+        package: &options.package,
+        types,
+        variants: union.variants.iter().map(|v| type_name(&v.name)).collect(),
+    };
+    // parent class is referenced from variant classes
+    let name = qualified_name(type_name(&union.name), &scope);
+    let params = type_params(&union.params);
+    let mut text = kdoc(union.doc.as_deref(), "");
+    if options.enum_tag != "type" {
+        text.push_str(&format!(
+            "@OptIn(ExperimentalSerializationApi::class)\n@JsonClassDiscriminator(\"{}\")\n",
+            options.enum_tag
+        ));
+    }
+    // type params are covariant, so variants without fields can be objects of `Nothing` type
+    let covariant: Vec<String> = union
+        .params
+        .iter()
+        .map(|p| format!("out {}", type_name(p)))
+        .collect();
+    text.push_str("@Serializable\n");
+    if covariant.is_empty() {
+        text.push_str(&format!("sealed class {} {{", type_name(&union.name)));
+    } else {
+        text.push_str(&format!(
+            "sealed class {}<{}> {{",
+            type_name(&union.name),
+            covariant.join(", ")
+        ));
+    }
+    if !union.variants.is_empty() {
+        text.push('\n');
+    }
+    for (i, variant) in union.variants.iter().enumerate() {
+        if i > 0 {
+            text.push('\n');
+        }
+        text.push_str(&kdoc(variant.doc.as_deref(), INDENT));
+        text.push_str(&format!(
+            "{}@Serializable\n{}@SerialName(\"{}\")\n",
+            INDENT,
+            INDENT,
+            variant.name.get()
+        ));
+        // only variants of records without fields are objects
+        let fields = match &variant.payload {
+            TypeRef::Record { .. } => variant.fields.clone(),
+            payload => vec![Field {
+                name: Name::new("value".to_owned(), Position::default()).unwrap(),
+                field_type: payload.clone(),
+                is_recursive: false,
+                doc: None,
+            }],
+        };
+        if fields.is_empty() {
+            let nothing = vec![kotlin_name("Nothing", "kotlin", &scope); union.params.len()];
+            let parent = if nothing.is_empty() {
+                format!("{}()", name)
+            } else {
+                format!("{}<{}>()", name, nothing.join(", "))
+            };
+            text.push_str(&format!(
+                "{}object {} : {}\n",
+                INDENT,
+                type_name(&variant.name),
+                parent
+            ));
+        } else {
+            text.push_str(&class_header(
+                &type_name(&variant.name),
+                &params,
+                &fields,
+                INDENT,
+            ));
+            text.push_str(&class_fields(&fields, INDENT, &scope));
+            text.pop();
+            text.push_str(&format!(" : {}{}()\n", name, params));
+        }
+    }
+    text.push_str("}\n");
+    text
+}
+
+fn class_header(name: &str, params: &str, fields: &[Field], indent: &str) -> String {
+    if fields.is_empty() {
+        // data classes should have at least one property
+        format!("{}class {}{}", indent, name, params)
+    } else {
+        format!("{}data class {}{}(", indent, name, params)
+    }
+}
+
+fn class_fields(fields: &[Field], indent: &str, scope: &Scope) -> String {
+    if fields.is_empty() {
+        return "\n".to_owned();
+    }
+    let mut text = "\n".to_owned();
+    for field in fields {
+        let name = field_name(&field.name);
+        text.push_str(&kdoc(
+            field.doc.as_deref(),
+            &format!("{}{}", indent, INDENT),
+        ));
+        if name.trim_matches('`') != field.name.get() {
+            text.push_str(&format!(
+                "{}{}@SerialName(\"{}\")\n",
+                indent,
+                INDENT,
+                field.name.get()
+            ));
+        }
+        text.push_str(&format!(
+            "{}{}val {}: {},\n",
+            indent,
+            INDENT,
+            name,
+            kotlin_type(&field.field_type, scope)
+        ));
+    }
+    text.push_str(&format!("{})\n", indent));
+    text
+}
+
+fn generate_const(const_decl: &ConstDecl, scope: &Scope) -> String {
+    let value = match (&const_decl.value, &const_decl.const_type) {
+        // `$` starts a template in Kotlin strings
+        (Literal::String(_), _) => const_decl.value.to_string().replace('$', "\\$"),
+        (Literal::Integer(v), SimpleType::Float) => format!("{}.0", v),
+        (
+            Literal::Integer(v),
+            SimpleType::UInt8 | SimpleType::UInt16 | SimpleType::UInt32 | SimpleType::UInt64,
+        ) => format!("{}u", v),
+        (value, _) => value.to_string(),
+    };
+    let mut text = kdoc(const_decl.doc.as_deref(), "");
+    text.push_str(&format!(
+        "const val {}: {} = {}\n",
+        const_decl.name.apply_style(WritingStyle::Upper, "_"),
+        kotlin_name(simple_type(&const_decl.const_type), "kotlin", scope),
+        value
+    ));
+    text
+}
+
+fn kdoc(doc: Option<&str>, indent: &str) -> String {
+    match doc.map(|d| d.split('\n').collect::<Vec<_>>()) {
+        None => "".to_owned(),
+        Some(lines) if lines.len() == 1 => format!("{}/** {} */\n", indent, lines[0]),
+        Some(_) => {
+            let mut text = format!("{}/**\n", indent);
+            text.push_str(&doc_lines(doc, indent, " * "));
+            text.push_str(&format!("{} */\n", indent));
+            text
+        }
+    }
+}
+
+fn type_name(name: &Name) -> String {
+    name.apply_style(WritingStyle::UpperCamel, "")
+}
+
+fn field_name(name: &Name) -> String {
+    let name = name.apply_style(WritingStyle::LowerCamel, "");
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("`{}`", name)
+    } else {
+        name
+    }
+}
+
+fn type_params(params: &[Name]) -> String {
+    if params.is_empty() {
+        "".to_owned()
+    } else {
+        let params: Vec<String> = params.iter().map(type_name).collect();
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn kotlin_type(type_ref: &TypeRef, scope: &Scope) -> String {
+    match type_ref {
+        TypeRef::Simple(t) => kotlin_name(simple_type(t), "kotlin", scope),
+        TypeRef::List(t) => format!(
+            "{}<{}>",
+            kotlin_name("List", "kotlin.collections", scope),
+            kotlin_type(t, scope)
+        ),
+        TypeRef::Param(name) => type_name(name),
+        TypeRef::Record { name, args } | TypeRef::Union { name, args } => {
+            let name = qualified_name(type_name(name), scope);
+            if args.is_empty() {
+                name
+            } else {
+                let args: Vec<String> = args.iter().map(|a| kotlin_type(a, scope)).collect();
+                format!("{}<{}>", name, args.join(", "))
+            }
+        }
+    }
+}
+
+// top level type, eg. `models.Admin` inside of `Role` with `Admin` variant
+fn qualified_name(name: String, scope: &Scope) -> String {
+    if scope.variants.contains(&name) {
+        format!("{}.{}", scope.package, name)
+    } else {
+        name
+    }
+}
+
+// kotlin type, eg. `kotlin.String` if there is a `String` class in the spec
+fn kotlin_name(name: &str, package: &str, scope: &Scope) -> String {
+    let name = name.to_owned();
+    if scope.types.contains(&name) || scope.variants.contains(&name) {
+        format!("{}.{}", package, name)
+    } else {
+        name
+    }
+}
+
+fn simple_type(simple_type: &SimpleType) -> &'static str {
+    match simple_type {
+        SimpleType::Integer => "Long",
+        SimpleType::Float => "Double",
+        SimpleType::Boolean => "Boolean",
+        SimpleType::Int8 => "Byte",
+        SimpleType::Int16 => "Short",
+        SimpleType::Int32 => "Int",
+        SimpleType::Int64 => "Long",
+        SimpleType::UInt8 => "UByte",
+        SimpleType::UInt16 => "UShort",
+        SimpleType::UInt32 => "UInt",
+        SimpleType::UInt64 => "ULong",
+        SimpleType::String
+        | SimpleType::Bytes
+        | SimpleType::Decimal
+        | SimpleType::Date
+        | SimpleType::DateTime
+        | SimpleType::Duration
+        | SimpleType::Uuid => "String",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_generate() {
+        let data = "
+            /-- user of the service
+            .struct user
+                id: int
+                first-name: str /-- given name
+                in: bool
+                roles: list[role]
+                manager: option[user]
+
+            .struct none
+            .struct some[t]
+                value: t
+            .enum option[t]
+                none
+                some[t]
+
+            .enum role
+                /-- full access
+                admin: user
+                guest: none
+
+            .const max-page-size: u32 = 100
+            .const currency: str = \"$\"
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &KotlinOptions::default()),
+            r#"package models
+
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+
+/** user of the service */
+@Serializable
+data class User(
+    val id: Long,
+    /** given name */
+    @SerialName("first-name")
+    val firstName: String,
+    val `in`: Boolean,
+    val roles: List<Role>,
+    val manager: Option<User>,
+)
+
+@Serializable
+class None
+
+@Serializable
+data class Some<T>(
+    val value: T,
+)
+
+@Serializable
+sealed class Option<out T> {
+    @Serializable
+    @SerialName("none")
+    object None : Option<Nothing>()
+
+    @Serializable
+    @SerialName("some")
+    data class Some<T>(
+        val value: T,
+    ) : Option<T>()
+}
+
+@Serializable
+sealed class Role {
+    /** full access */
+    @Serializable
+    @SerialName("admin")
+    data class Admin(
+        val id: Long,
+        /** given name */
+        @SerialName("first-name")
+        val firstName: String,
+        val `in`: Boolean,
+        val roles: List<Role>,
+        val manager: Option<User>,
+    ) : Role()
+
+    @Serializable
+    @SerialName("guest")
+    object Guest : Role()
+}
+
+const val MAX_PAGE_SIZE: UInt = 100u
+
+const val CURRENCY: String = "\$"
+"#
+        );
+        let options = KotlinOptions {
+            package: "api".to_owned(),
+            enum_tag: "kind".to_owned(),
+        };
+        let spec =
+            LeapSpec::new(Parser::parse(".struct user\n.enum role\n    admin: user").unwrap());
+        assert_eq!(
+            generate(&spec, &options),
+            r#"package api
+
+import kotlinx.serialization.ExperimentalSerializationApi
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+import kotlinx.serialization.json.JsonClassDiscriminator
+
+@Serializable
+class User
+
+@OptIn(ExperimentalSerializationApi::class)
+@JsonClassDiscriminator("kind")
+@Serializable
+sealed class Role {
+    @Serializable
+    @SerialName("admin")
+    object Admin : Role()
+}
+"#
+        );
+    }
+    #[test]
+    fn test_edge_cases() {
+        // variants with the same type, names which are kotlin keywords or hide other types
+        let data = "
+            .struct admin
+                name: str
+            .struct string
+                value: str
+            .struct user
+                deputy: list[admin]
+                class: str
+                is: bool
+            .enum role
+                admin: user
+                owner: user
+                list: string
+            .const ratio: float = 1
+            .const title: str = \"$admin\"
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &KotlinOptions::default()),
+            r#"package models
+
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+
+@Serializable
+data class Admin(
+    val name: kotlin.String,
+)
+
+@Serializable
+data class String(
+    val value: kotlin.String,
+)
+
+@Serializable
+data class User(
+    val deputy: List<Admin>,
+    val `class`: kotlin.String,
+    val `is`: Boolean,
+)
+
+@Serializable
+sealed class Role {
+    @Serializable
+    @SerialName("admin")
+    data class Admin(
+        val deputy: kotlin.collections.List<models.Admin>,
+        val `class`: kotlin.String,
+        val `is`: Boolean,
+    ) : Role()
+
+    @Serializable
+    @SerialName("owner")
+    data class Owner(
+        val deputy: kotlin.collections.List<models.Admin>,
+        val `class`: kotlin.String,
+        val `is`: Boolean,
+    ) : Role()
+
+    @Serializable
+    @SerialName("list")
+    data class List(
+        val value: kotlin.String,
+    ) : Role()
+}
+
+const val RATIO: Double = 1.0
+
+const val TITLE: kotlin.String = "\$admin"
+"#
+        );
+    }
+
+    #[test]
+    fn test_value_variants() {
+        // variants of records without fields are objects, values which are not records are
+        // in `value` field
+        let data = "
+            .struct empty
+            .enum option[t]
+                some: t
+                none: empty
+            .enum tree
+                leaf: int
+                branch: list[tree]
+                nested: option[tree]
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &KotlinOptions::default()),
+            r#"package models
+
+import kotlinx.serialization.SerialName
+import kotlinx.serialization.Serializable
+
+@Serializable
+class Empty
+
+@Serializable
+sealed class Option<out T> {
+    @Serializable
+    @SerialName("some")
+    data class Some<T>(
+        val value: T,
+    ) : Option<T>()
+
+    @Serializable
+    @SerialName("none")
+    object None : Option<Nothing>()
+}
+
+@Serializable
+sealed class Tree {
+    @Serializable
+    @SerialName("leaf")
+    data class Leaf(
+        val value: Long,
+    ) : Tree()
+
+    @Serializable
+    @SerialName("branch")
+    data class Branch(
+        val value: List<Tree>,
+    ) : Tree()
+
+    @Serializable
+    @SerialName("nested")
+    data class Nested(
+        val value: Option<Tree>,
+    ) : Tree()
+}
+"#
+        );
+    }
+}
@@ -0,0 +1,202 @@
+// target independent model of a spec for generators, references to types are resolved to
+// records or unions and fields of variant records are applied to the variant type args
+use crate::leaptypes::{LeapSpec, LeapType, Literal, Name, Prop, SimpleType, ValueType};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub types: Vec<TypeDecl>,
+    // only constants of simple types
+    pub consts: Vec<ConstDecl>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeDecl {
+    Record(Record),
+    Union(Union),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub name: Name,
+    pub params: Vec<Name>,
+    pub fields: Vec<Field>,
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    // name from spec is used on the wire
+    pub name: Name,
+    pub field_type: TypeRef,
+    pub is_recursive: bool,
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Union {
+    pub name: Name,
+    pub params: Vec<Name>,
+    pub variants: Vec<Variant>,
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+    pub name: Name,
+    pub payload: TypeRef,
+    // fields of the payload record with applied type args, on the wire they are next to the tag,
+    // empty if payload is not a record
+    pub fields: Vec<Field>,
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstDecl {
+    pub name: Name,
+    pub const_type: SimpleType,
+    pub value: Literal,
+    pub doc: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeRef {
+    Simple(SimpleType),
+    List(Box<TypeRef>),
+    Param(Name),
+    Record { name: Name, args: Vec<TypeRef> },
+    Union { name: Name, args: Vec<TypeRef> },
+}
+
+impl TypeRef {
+    pub fn is_union(&self) -> bool {
+        matches!(self, TypeRef::Union { .. })
+    }
+}
+
+pub fn lower(spec: &LeapSpec) -> Module {
+    let types = spec
+        .iter_type_refs()
+        .map(|t| match t {
+            LeapType::Struct(s) => TypeDecl::Record(Record {
+                name: s.name.clone(),
+                params: s.args.clone(),
+                fields: lower_fields(&s.props, spec),
+                doc: s.doc.clone(),
+            }),
+            LeapType::Enum(e) => TypeDecl::Union(Union {
+                name: e.name.clone(),
+                params: e.args.clone(),
+                variants: e.variants.iter().map(|v| lower_variant(v, spec)).collect(),
+                doc: e.doc.clone(),
+            }),
+        })
+        .collect();
+    let consts = spec
+        .iter_consts()
+        .filter_map(|c| match &c.value_type {
+            ValueType::Simple(t, _) => Some(ConstDecl {
+                name: c.name.clone(),
+                const_type: t.clone(),
+                value: c.value.clone(),
+                doc: c.doc.clone(),
+            }),
+            _ => None,
+        })
+        .collect();
+    Module { types, consts }
+}
+
+fn lower_fields(props: &[Prop], spec: &LeapSpec) -> Vec<Field> {
+    props
+        .iter()
+        .map(|p| Field {
+            name: p.name.clone(),
+            field_type: lower_type(&p.prop_type, spec),
+            is_recursive: p.is_recursive,
+            doc: p.doc.clone(),
+        })
+        .collect()
+}
+
+fn lower_variant(variant: &Prop, spec: &LeapSpec) -> Variant {
+    let fields = match &variant.prop_type {
+        ValueType::LeapType { name, args, .. } => match spec.get_type_by_name(name.get()) {
+            Some(t @ LeapType::Struct(_)) => match t.apply_args(args) {
+                LeapType::Struct(s) => lower_fields(&s.props, spec),
+                LeapType::Enum(_) => vec![],
+            },
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+    Variant {
+        name: variant.name.clone(),
+        payload: lower_type(&variant.prop_type, spec),
+        fields,
+        doc: variant.doc.clone(),
+    }
+}
+
+fn lower_type(value_type: &ValueType, spec: &LeapSpec) -> TypeRef {
+    match value_type {
+        ValueType::Simple(t, _) => TypeRef::Simple(t.clone()),
+        ValueType::List(t, _) => TypeRef::List(Box::new(lower_type(t, spec))),
+        ValueType::TypeArg(name) => TypeRef::Param(name.clone()),
+        ValueType::LeapType { name, args, .. } => {
+            let args = args.iter().map(|a| lower_type(a, spec)).collect();
+            if spec.is_enum_name(name.get()) {
+                TypeRef::Union {
+                    name: name.clone(),
+                    args,
+                }
+            } else {
+                TypeRef::Record {
+                    name: name.clone(),
+                    args,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_lower() {
+        let data = "
+            .struct some[t]
+                value: t
+            .struct none
+            .enum option[t]
+                none
+                some[t]
+            .struct user
+                manager: option[user]
+            .const max-size: int = 10
+            .const no-manager: option[user] = option.none
+        ";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        spec.mark_recursive_props();
+        let module = lower(&spec);
+        assert_eq!(module.types.len(), 4);
+        let TypeDecl::Union(option) = &module.types[2] else {
+            panic!("expected union");
+        };
+        assert_eq!(option.params[0].get(), "t");
+        assert_eq!(option.variants[0].fields, vec![]);
+        let some = &option.variants[1];
+        assert!(matches!(&some.payload, TypeRef::Record { name, .. } if name.get() == "some"));
+        assert_eq!(some.fields.len(), 1);
+        assert!(matches!(&some.fields[0].field_type, TypeRef::Param(n) if n.get() == "t"));
+        let TypeDecl::Record(user) = &module.types[3] else {
+            panic!("expected record");
+        };
+        assert!(user.fields[0].field_type.is_union());
+        assert!(user.fields[0].is_recursive);
+        assert_eq!(module.consts.len(), 1);
+        assert_eq!(module.consts[0].const_type, SimpleType::Integer);
+    }
+}
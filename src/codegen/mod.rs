@@ -0,0 +1,16 @@
+// code generators for other languages, every generator renders whole spec into a single source
+pub mod go;
+pub mod kotlin;
+pub mod lowering;
+pub mod python;
+pub mod rust;
+pub mod swift;
+pub mod typescript;
+
+// doc comment lines with prefix, eg. `/// ` in Rust
+fn doc_lines(doc: Option<&str>, indent: &str, prefix: &str) -> String {
+    doc.into_iter()
+        .flat_map(|d| d.split('\n'))
+        .map(|line| format!("{}{}{}\n", indent, prefix, line).replace(" \n", "\n"))
+        .collect()
+}
@@ -0,0 +1,1147 @@
+use std::collections::HashMap;
+
+use super::doc_lines;
+use crate::leaptypes::{
+    LeapConst, LeapEnum, LeapSpec, LeapStruct, LeapType, Literal, Name, Prop, SimpleType, ValueType,
+};
+use crate::naming::WritingStyle;
+
+const INDENT: &str = "    ";
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PythonClasses {
+    // `@dataclass` classes with `from_dict` and `to_dict` helpers
+    Dataclass,
+    // `TypedDict`s with names of fields from spec, dicts are used as is so there are no helpers,
+    // type arguments are `Any` as functional syntax of `TypedDict` doesn't support generics
+    TypedDict,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PythonOptions {
+    pub classes: PythonClasses,
+    // field with variant name in enum values, fields of struct variants are next to it,
+    // other values are in `value` field
+    pub enum_tag: String,
+}
+
+impl Default for PythonOptions {
+    fn default() -> Self {
+        PythonOptions {
+            classes: PythonClasses::Dataclass,
+            enum_tag: "type".to_owned(),
+        }
+    }
+}
+
+struct Generator<'a> {
+    spec: &'a LeapSpec,
+    options: &'a PythonOptions,
+    // order of types in module, to find references to types defined later
+    order: HashMap<&'a str, usize>,
+}
+
+// numbers are `int` or `float`, `bytes` (base64), `decimal`, `date`, `datetime`, `duration`
+// and `uuid` are strings
+pub fn generate(spec: &LeapSpec, options: &PythonOptions) -> String {
+    let generator = Generator {
+        spec,
+        options,
+        order: spec
+            .iter_type_refs()
+            .enumerate()
+            .map(|(i, t)| (t.name().get(), i))
+            .collect(),
+    };
+    let mut items = vec![generator.imports()];
+    let type_vars = generator.type_vars();
+    if !type_vars.is_empty() {
+        items.push(type_vars);
+    }
+    for (i, leap_type) in spec.iter_type_refs().enumerate() {
+        items.push(match (leap_type, options.classes) {
+            (LeapType::Struct(s), PythonClasses::Dataclass) => generator.dataclass(s, i),
+            (LeapType::Struct(s), PythonClasses::TypedDict) => generator.typed_dict(s, i),
+            (LeapType::Enum(e), PythonClasses::Dataclass) => {
+                let mut parts: Vec<String> = e
+                    .variants
+                    .iter()
+                    .map(|v| generator.variant_dataclass(e, v, i))
+                    .collect();
+                parts.push(generator.union(e));
+                parts.push(generator.enum_from_dict(e));
+                parts.push(generator.enum_to_dict(e));
+                parts.join("\n\n")
+            }
+            (LeapType::Enum(e), PythonClasses::TypedDict) => {
+                let mut parts: Vec<String> = e
+                    .variants
+                    .iter()
+                    .map(|v| generator.variant_typed_dict(e, v, i))
+                    .collect();
+                parts.push(generator.union(e));
+                parts.join("\n\n")
+            }
+        });
+    }
+    let consts: Vec<String> = spec.iter_consts().filter_map(generate_const).collect();
+    if !consts.is_empty() {
+        items.push(consts.concat());
+    }
+    items.join("\n\n")
+}
+
+impl Generator<'_> {
+    fn imports(&self) -> String {
+        match self.options.classes {
+            PythonClasses::Dataclass => "from dataclasses import dataclass\nfrom typing import Any, Callable, Dict, Generic, List, NoReturn, TypeVar, Union\n",
+            PythonClasses::TypedDict => "from typing import Any, List, Literal, NoReturn, TypedDict, Union\n",
+        }
+        .to_owned()
+    }
+
+    fn type_vars(&self) -> String {
+        if self.options.classes == PythonClasses::TypedDict {
+            return "".to_owned();
+        }
+        let mut names: Vec<String> = vec![];
+        for leap_type in self.spec.iter_type_refs() {
+            for arg in leap_type.args() {
+                let name = type_name(arg);
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+            .iter()
+            .map(|n| format!("{} = TypeVar(\"{}\")\n", n, n))
+            .collect()
+    }
+
+    fn dataclass(&self, leap_struct: &LeapStruct, index: usize) -> String {
+        let name = type_name(&leap_struct.name);
+        let params = type_params(&leap_struct.args);
+        let mut text = "@dataclass\n".to_owned();
+        if params.is_empty() {
+            text.push_str(&format!("class {}:\n", name));
+        } else {
+            text.push_str(&format!("class {}(Generic{}):\n", name, params));
+        }
+        text.push_str(&docstring(leap_struct.doc.as_deref(), INDENT));
+        for prop in &leap_struct.props {
+            text.push_str(&format!(
+                "{}{}: {}\n",
+                INDENT,
+                field_name(&prop.name),
+                self.annotation(&prop.prop_type, index)
+            ));
+            if let Some(doc) = &prop.doc {
+                text.push_str(&docstring(Some(doc), INDENT));
+            }
+        }
+        if leap_struct.doc.is_some() || !leap_struct.props.is_empty() {
+            text.push('\n');
+        }
+        // values of type arguments are converted with functions passed as arguments
+        let from_args: String = leap_struct
+            .args
+            .iter()
+            .map(|a| {
+                format!(
+                    ", from_{}: Callable[[Any], {}]",
+                    field_name(a),
+                    type_name(a)
+                )
+            })
+            .collect();
+        let to_args: String = leap_struct
+            .args
+            .iter()
+            .map(|a| format!(", to_{}: Callable[[{}], Any]", field_name(a), type_name(a)))
+            .collect();
+        text.push_str(&format!(
+            "{}@classmethod\n{}def from_dict(cls, data: Dict[str, Any]{}) -> \"{}{}\":\n",
+            INDENT, INDENT, from_args, name, params
+        ));
+        text.push_str(&format!("{}{}return cls(", INDENT, INDENT));
+        for prop in &leap_struct.props {
+            let value = format!("data[\"{}\"]", prop.name.get());
+            text.push_str(&format!(
+                "\n{}{}{}={},",
+                INDENT,
+                INDENT.repeat(2),
+                field_name(&prop.name),
+                self.decode_expr(&prop.prop_type, &value, 0)
+            ));
+        }
+        if !leap_struct.props.is_empty() {
+            text.push_str(&format!("\n{}", INDENT.repeat(2)));
+        }
+        text.push_str(")\n\n");
+        text.push_str(&format!(
+            "{}def to_dict(self{}) -> Dict[str, Any]:\n",
+            INDENT, to_args
+        ));
+        text.push_str(&format!("{}{}return {{", INDENT, INDENT));
+        for prop in &leap_struct.props {
+            let value = format!("self.{}", field_name(&prop.name));
+            text.push_str(&format!(
+                "\n{}\"{}\": {},",
+                INDENT.repeat(3),
+                prop.name.get(),
+                self.encode_expr(&prop.prop_type, &value, 0)
+            ));
+        }
+        if !leap_struct.props.is_empty() {
+            text.push_str(&format!("\n{}", INDENT.repeat(2)));
+        }
+        text.push_str("}\n");
+        text
+    }
+
+    fn typed_dict(&self, leap_struct: &LeapStruct, index: usize) -> String {
+        let name = type_name(&leap_struct.name);
+        let mut text = doc_lines(leap_struct.doc.as_deref(), "", "# ");
+        text.push_str(&format!("{} = TypedDict(\"{}\", {{", name, name));
+        for prop in &leap_struct.props {
+            text.push('\n');
+            text.push_str(&doc_lines(prop.doc.as_deref(), INDENT, "# "));
+            text.push_str(&format!(
+                "{}\"{}\": {},",
+                INDENT,
+                prop.name.get(),
+                self.annotation(&prop.prop_type, index)
+            ));
+        }
+        if !leap_struct.props.is_empty() {
+            text.push('\n');
+        }
+        text.push_str("})\n");
+        text
+    }
+
+    // variant of enum wraps its value, so variants with the same type can be distinguished
+    fn variant_dataclass(&self, leap_enum: &LeapEnum, variant: &Prop, index: usize) -> String {
+        let params = type_params(&leap_enum.args);
+        let mut text = "@dataclass\n".to_owned();
+        if params.is_empty() {
+            text.push_str(&format!(
+                "class {}:\n",
+                self.variant_class(leap_enum, variant)
+            ));
+        } else {
+            text.push_str(&format!(
+                "class {}(Generic{}):\n",
+                self.variant_class(leap_enum, variant),
+                params
+            ));
+        }
+        text.push_str(&docstring(variant.doc.as_deref(), INDENT));
+        text.push_str(&format!(
+            "{}value: {}\n",
+            INDENT,
+            self.annotation(&variant.prop_type, index)
+        ));
+        text
+    }
+
+    // fields of the variant struct together with the tag
+    fn variant_typed_dict(&self, leap_enum: &LeapEnum, variant: &Prop, index: usize) -> String {
+        let name = self.variant_class(leap_enum, variant);
+        let mut text = doc_lines(variant.doc.as_deref(), "", "# ");
+        text.push_str(&format!(
+            "{} = TypedDict(\"{}\", {{\n{}\"{}\": Literal[\"{}\"],\n",
+            name,
+            name,
+            INDENT,
+            self.options.enum_tag,
+            variant.name.get()
+        ));
+        let props = match &variant.prop_type {
+            ValueType::LeapType { name, .. } => self
+                .spec
+                .get_type_by_name(name.get())
+                .and_then(|t| t.as_struct())
+                .map(|s| &s.props),
+            _ => None,
+        };
+        let Some(props) = props else {
+            text.push_str(&format!(
+                "{}\"value\": {},\n}})\n",
+                INDENT,
+                self.annotation(&variant.prop_type, index)
+            ));
+            return text;
+        };
+        for prop in props {
+            text.push_str(&format!(
+                "{}\"{}\": {},\n",
+                INDENT,
+                prop.name.get(),
+                self.annotation(&prop.prop_type, index)
+            ));
+        }
+        text.push_str("})\n");
+        text
+    }
+
+    fn union(&self, leap_enum: &LeapEnum) -> String {
+        let mut text = doc_lines(leap_enum.doc.as_deref(), "", "# ");
+        let params = match self.options.classes {
+            PythonClasses::Dataclass => type_params(&leap_enum.args),
+            PythonClasses::TypedDict => "".to_owned(),
+        };
+        let variants: Vec<String> = leap_enum
+            .variants
+            .iter()
+            .map(|v| format!("{}{}", self.variant_class(leap_enum, v), params))
+            .collect();
+        if variants.is_empty() {
+            text.push_str(&format!("{} = NoReturn\n", type_name(&leap_enum.name)));
+        } else {
+            text.push_str(&format!(
+                "{} = Union[{}]\n",
+                type_name(&leap_enum.name),
+                variants.join(", ")
+            ));
+        }
+        text
+    }
+
+    fn enum_from_dict(&self, leap_enum: &LeapEnum) -> String {
+        let mut text = format!(
+            "def {}_from_dict(data: Dict[str, Any]{}) -> {}:\n",
+            field_name(&leap_enum.name),
+            converter_args("from", &leap_enum.args),
+            type_name(&leap_enum.name)
+        );
+        text.push_str(&format!(
+            "{}tag = data[\"{}\"]\n",
+            INDENT, self.options.enum_tag
+        ));
+        for variant in &leap_enum.variants {
+            text.push_str(&format!(
+                "{}if tag == \"{}\":\n{}{}return {}({})\n",
+                INDENT,
+                variant.name.get(),
+                INDENT,
+                INDENT,
+                self.variant_class(leap_enum, variant),
+                if self.is_struct_variant(variant) {
+                    self.decode_expr(&variant.prop_type, "data", 0)
+                } else {
+                    self.decode_expr(&variant.prop_type, "data[\"value\"]", 0)
+                }
+            ));
+        }
+        text.push_str(&format!(
+            "{}raise ValueError(f\"Unknown variant {{tag}}\")\n",
+            INDENT
+        ));
+        text
+    }
+
+    fn enum_to_dict(&self, leap_enum: &LeapEnum) -> String {
+        let mut text = format!(
+            "def {}_to_dict(value: {}{}) -> Dict[str, Any]:\n",
+            field_name(&leap_enum.name),
+            type_name(&leap_enum.name),
+            converter_args("to", &leap_enum.args)
+        );
+        for variant in &leap_enum.variants {
+            let value = self.encode_expr(&variant.prop_type, "value.value", 0);
+            text.push_str(&format!(
+                "{}if isinstance(value, {}):\n{}{}return {{\"{}\": \"{}\", {}}}\n",
+                INDENT,
+                self.variant_class(leap_enum, variant),
+                INDENT,
+                INDENT,
+                self.options.enum_tag,
+                variant.name.get(),
+                if self.is_struct_variant(variant) {
+                    format!("**{}", value)
+                } else {
+                    format!("\"value\": {}", value)
+                }
+            ));
+        }
+        text.push_str(&format!(
+            "{}raise ValueError(f\"Unknown variant {{value}}\")\n",
+            INDENT
+        ));
+        text
+    }
+
+    // fields of struct variants are next to the tag, other values are in `value` field
+    fn is_struct_variant(&self, variant: &Prop) -> bool {
+        matches!(&variant.prop_type, ValueType::LeapType { name, .. }
+            if self.spec.get_type_by_name(name.get()).is_some_and(|t| t.is_struct()))
+    }
+
+    // name of enum followed by name of variant (`RoleAdmin`), suffixed if it is taken by a type
+    fn variant_class(&self, leap_enum: &LeapEnum, variant: &Prop) -> String {
+        let name = escape(format!(
+            "{}{}",
+            leap_enum.name.apply_style(WritingStyle::UpperCamel, ""),
+            variant.name.apply_style(WritingStyle::UpperCamel, "")
+        ));
+        if self
+            .spec
+            .iter_type_refs()
+            .any(|t| type_name(t.name()) == name)
+        {
+            format!("{}Variant", name)
+        } else {
+            name
+        }
+    }
+
+    // type annotation, quoted if it references types defined later (forward reference),
+    // including recursive references
+    fn annotation(&self, value_type: &ValueType, index: usize) -> String {
+        let text = self.python_type(value_type);
+        if self.has_forward_ref(value_type, index) {
+            format!("\"{}\"", text)
+        } else {
+            text
+        }
+    }
+
+    fn has_forward_ref(&self, value_type: &ValueType, index: usize) -> bool {
+        match value_type {
+            ValueType::Simple(..) | ValueType::TypeArg(_) => false,
+            ValueType::List(t, _) => self.has_forward_ref(t, index),
+            ValueType::LeapType { name, args, .. } => {
+                self.order.get(name.get()).is_some_and(|i| *i >= index)
+                    || args.iter().any(|a| self.has_forward_ref(a, index))
+            }
+        }
+    }
+
+    fn python_type(&self, value_type: &ValueType) -> String {
+        match value_type {
+            ValueType::Simple(t, _) => simple_type(t).to_owned(),
+            ValueType::List(t, _) => format!("List[{}]", self.python_type(t)),
+            ValueType::TypeArg(name) => match self.options.classes {
+                PythonClasses::Dataclass => type_name(name),
+                PythonClasses::TypedDict => "Any".to_owned(),
+            },
+            ValueType::LeapType { name, args, .. } => {
+                if args.is_empty() || self.options.classes == PythonClasses::TypedDict {
+                    type_name(name)
+                } else {
+                    let args: Vec<String> = args.iter().map(|a| self.python_type(a)).collect();
+                    format!("{}[{}]", type_name(name), args.join(", "))
+                }
+            }
+        }
+    }
+
+    // converts value from dict representation, `depth` is used for names of loop variables
+    fn decode_expr(&self, value_type: &ValueType, value: &str, depth: usize) -> String {
+        match value_type {
+            ValueType::Simple(..) => value.to_owned(),
+            ValueType::List(t, _) => {
+                let item = format!("v{}", depth);
+                format!(
+                    "[{} for {} in {}]",
+                    self.decode_expr(t, &item, depth + 1),
+                    item,
+                    value
+                )
+            }
+            ValueType::TypeArg(name) => format!("from_{}({})", field_name(name), value),
+            ValueType::LeapType { name, args, .. } => {
+                let item = format!("v{}", depth);
+                let converters: String = args
+                    .iter()
+                    .map(|a| {
+                        format!(
+                            ", lambda {}: {}",
+                            item,
+                            self.decode_expr(a, &item, depth + 1)
+                        )
+                    })
+                    .collect();
+                if self.spec.is_enum_name(name.get()) {
+                    format!("{}_from_dict({}{})", field_name(name), value, converters)
+                } else {
+                    format!("{}.from_dict({}{})", type_name(name), value, converters)
+                }
+            }
+        }
+    }
+
+    // converts value to dict representation
+    fn encode_expr(&self, value_type: &ValueType, value: &str, depth: usize) -> String {
+        match value_type {
+            ValueType::Simple(..) => value.to_owned(),
+            ValueType::List(t, _) => {
+                let item = format!("v{}", depth);
+                format!(
+                    "[{} for {} in {}]",
+                    self.encode_expr(t, &item, depth + 1),
+                    item,
+                    value
+                )
+            }
+            ValueType::TypeArg(name) => format!("to_{}({})", field_name(name), value),
+            ValueType::LeapType { name, args, .. } => {
+                let item = format!("v{}", depth);
+                let converters: String = args
+                    .iter()
+                    .map(|a| {
+                        format!(
+                            ", lambda {}: {}",
+                            item,
+                            self.encode_expr(a, &item, depth + 1)
+                        )
+                    })
+                    .collect();
+                if self.spec.is_enum_name(name.get()) {
+                    format!("{}_to_dict({}{})", field_name(name), value, converters)
+                } else {
+                    format!("{}.to_dict({})", value, converters.trim_start_matches(", "))
+                }
+            }
+        }
+    }
+}
+
+// only constants of simple types are generated
+fn generate_const(leap_const: &LeapConst) -> Option<String> {
+    let ValueType::Simple(t, _) = &leap_const.value_type else {
+        return None;
+    };
+    let value = match &leap_const.value {
+        Literal::Boolean(true) => "True".to_owned(),
+        Literal::Boolean(false) => "False".to_owned(),
+        Literal::Integer(v) if *t == SimpleType::Float => format!("{}.0", v),
+        value => value.to_string(),
+    };
+    let mut text = doc_lines(leap_const.doc.as_deref(), "", "# ");
+    text.push_str(&format!(
+        "{}: {} = {}\n",
+        leap_const.name.apply_style(WritingStyle::Upper, "_"),
+        simple_type(t),
+        value
+    ));
+    Some(text)
+}
+
+fn converter_args(direction: &str, args: &[Name]) -> String {
+    args.iter()
+        .map(|a| format!(", {}_{}: Callable[[Any], Any]", direction, field_name(a)))
+        .collect()
+}
+
+fn docstring(doc: Option<&str>, indent: &str) -> String {
+    match doc {
+        None => "".to_owned(),
+        Some(doc) if !doc.contains('\n') => format!("{}\"\"\"{}\"\"\"\n", indent, doc),
+        Some(doc) => {
+            let mut text = format!("{}\"\"\"", indent);
+            for (i, line) in doc.split('\n').enumerate() {
+                if i > 0 {
+                    text.push_str(indent);
+                }
+                text.push_str(line);
+                text.push('\n');
+            }
+            text.push_str(&format!("{}\"\"\"\n", indent));
+            text
+        }
+    }
+}
+
+fn escape(name: String) -> String {
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+fn type_name(name: &Name) -> String {
+    escape(name.apply_style(WritingStyle::UpperCamel, ""))
+}
+
+fn field_name(name: &Name) -> String {
+    escape(name.apply_style(WritingStyle::Lower, "_"))
+}
+
+fn type_params(args: &[Name]) -> String {
+    if args.is_empty() {
+        "".to_owned()
+    } else {
+        let args: Vec<String> = args.iter().map(type_name).collect();
+        format!("[{}]", args.join(", "))
+    }
+}
+
+fn simple_type(simple_type: &SimpleType) -> &'static str {
+    match simple_type {
+        SimpleType::Boolean => "bool",
+        SimpleType::Float => "float",
+        SimpleType::Integer
+        | SimpleType::Int8
+        | SimpleType::Int16
+        | SimpleType::Int32
+        | SimpleType::Int64
+        | SimpleType::UInt8
+        | SimpleType::UInt16
+        | SimpleType::UInt32
+        | SimpleType::UInt64 => "int",
+        SimpleType::String
+        | SimpleType::Bytes
+        | SimpleType::Decimal
+        | SimpleType::Date
+        | SimpleType::DateTime
+        | SimpleType::Duration
+        | SimpleType::Uuid => "str",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    const DATA: &str = "
+        /-- user of the service
+        .struct user
+            id: int
+            first-name: str /-- given name
+            roles: list[role]
+            manager: option[user]
+
+        .struct none
+        .struct some[t]
+            value: t
+        .enum option[t]
+            none
+            some[t]
+
+        .enum role
+            admin: user
+            guest: none
+
+        .const max-page-size: int = 100
+        .const is-public: bool = true
+    ";
+
+    #[test]
+    fn test_dataclasses() {
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(DATA).unwrap());
+        assert_eq!(
+            generate(&spec, &PythonOptions::default()),
+            r#"from dataclasses import dataclass
+from typing import Any, Callable, Dict, Generic, List, NoReturn, TypeVar, Union
+
+
+T = TypeVar("T")
+
+
+@dataclass
+class User:
+    """user of the service"""
+    id: int
+    first_name: str
+    """given name"""
+    roles: "List[Role]"
+    manager: "Option[User]"
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "User":
+        return cls(
+            id=data["id"],
+            first_name=data["first-name"],
+            roles=[role_from_dict(v0) for v0 in data["roles"]],
+            manager=option_from_dict(data["manager"], lambda v0: User.from_dict(v0)),
+        )
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "id": self.id,
+            "first-name": self.first_name,
+            "roles": [role_to_dict(v0) for v0 in self.roles],
+            "manager": option_to_dict(self.manager, lambda v0: v0.to_dict()),
+        }
+
+
+@dataclass
+class None_:
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "None_":
+        return cls()
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {}
+
+
+@dataclass
+class Some(Generic[T]):
+    value: T
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any], from_t: Callable[[Any], T]) -> "Some[T]":
+        return cls(
+            value=from_t(data["value"]),
+        )
+
+    def to_dict(self, to_t: Callable[[T], Any]) -> Dict[str, Any]:
+        return {
+            "value": to_t(self.value),
+        }
+
+
+@dataclass
+class OptionNone(Generic[T]):
+    value: None_
+
+
+@dataclass
+class OptionSome(Generic[T]):
+    value: Some[T]
+
+
+Option = Union[OptionNone[T], OptionSome[T]]
+
+
+def option_from_dict(data: Dict[str, Any], from_t: Callable[[Any], Any]) -> Option:
+    tag = data["type"]
+    if tag == "none":
+        return OptionNone(None_.from_dict(data))
+    if tag == "some":
+        return OptionSome(Some.from_dict(data, lambda v0: from_t(v0)))
+    raise ValueError(f"Unknown variant {tag}")
+
+
+def option_to_dict(value: Option, to_t: Callable[[Any], Any]) -> Dict[str, Any]:
+    if isinstance(value, OptionNone):
+        return {"type": "none", **value.value.to_dict()}
+    if isinstance(value, OptionSome):
+        return {"type": "some", **value.value.to_dict(lambda v0: to_t(v0))}
+    raise ValueError(f"Unknown variant {value}")
+
+
+@dataclass
+class RoleAdmin:
+    value: User
+
+
+@dataclass
+class RoleGuest:
+    value: None_
+
+
+Role = Union[RoleAdmin, RoleGuest]
+
+
+def role_from_dict(data: Dict[str, Any]) -> Role:
+    tag = data["type"]
+    if tag == "admin":
+        return RoleAdmin(User.from_dict(data))
+    if tag == "guest":
+        return RoleGuest(None_.from_dict(data))
+    raise ValueError(f"Unknown variant {tag}")
+
+
+def role_to_dict(value: Role) -> Dict[str, Any]:
+    if isinstance(value, RoleAdmin):
+        return {"type": "admin", **value.value.to_dict()}
+    if isinstance(value, RoleGuest):
+        return {"type": "guest", **value.value.to_dict()}
+    raise ValueError(f"Unknown variant {value}")
+
+
+MAX_PAGE_SIZE: int = 100
+IS_PUBLIC: bool = True
+"#
+        );
+    }
+
+    #[test]
+    fn test_typed_dicts() {
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(DATA).unwrap());
+        let options = PythonOptions {
+            classes: PythonClasses::TypedDict,
+            ..Default::default()
+        };
+        assert_eq!(
+            generate(&spec, &options),
+            r#"from typing import Any, List, Literal, NoReturn, TypedDict, Union
+
+
+# user of the service
+User = TypedDict("User", {
+    "id": int,
+    # given name
+    "first-name": str,
+    "roles": "List[Role]",
+    "manager": "Option",
+})
+
+
+None_ = TypedDict("None_", {})
+
+
+Some = TypedDict("Some", {
+    "value": Any,
+})
+
+
+OptionNone = TypedDict("OptionNone", {
+    "type": Literal["none"],
+})
+
+
+OptionSome = TypedDict("OptionSome", {
+    "type": Literal["some"],
+    "value": Any,
+})
+
+
+Option = Union[OptionNone, OptionSome]
+
+
+RoleAdmin = TypedDict("RoleAdmin", {
+    "type": Literal["admin"],
+    "id": int,
+    "first-name": str,
+    "roles": "List[Role]",
+    "manager": Option,
+})
+
+
+RoleGuest = TypedDict("RoleGuest", {
+    "type": Literal["guest"],
+})
+
+
+Role = Union[RoleAdmin, RoleGuest]
+
+
+MAX_PAGE_SIZE: int = 100
+IS_PUBLIC: bool = True
+"#
+        );
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        // variants with the same type, names which are python keywords or taken by types
+        let data = "
+            .struct user
+                class: str
+                from: list[user]
+                ratio: float
+            .struct role-admin
+            .enum role
+                admin: user
+                owner: user
+                none: role-admin
+            .struct holder
+                role: role
+                lambda: int
+            .const scale: float = 1
+            .const none: bool = false
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &PythonOptions::default()),
+            r#"from dataclasses import dataclass
+from typing import Any, Callable, Dict, Generic, List, NoReturn, TypeVar, Union
+
+
+@dataclass
+class User:
+    class_: str
+    from_: "List[User]"
+    ratio: float
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "User":
+        return cls(
+            class_=data["class"],
+            from_=[User.from_dict(v0) for v0 in data["from"]],
+            ratio=data["ratio"],
+        )
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "class": self.class_,
+            "from": [v0.to_dict() for v0 in self.from_],
+            "ratio": self.ratio,
+        }
+
+
+@dataclass
+class RoleAdmin:
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "RoleAdmin":
+        return cls()
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {}
+
+
+@dataclass
+class RoleAdminVariant:
+    value: User
+
+
+@dataclass
+class RoleOwner:
+    value: User
+
+
+@dataclass
+class RoleNone:
+    value: RoleAdmin
+
+
+Role = Union[RoleAdminVariant, RoleOwner, RoleNone]
+
+
+def role_from_dict(data: Dict[str, Any]) -> Role:
+    tag = data["type"]
+    if tag == "admin":
+        return RoleAdminVariant(User.from_dict(data))
+    if tag == "owner":
+        return RoleOwner(User.from_dict(data))
+    if tag == "none":
+        return RoleNone(RoleAdmin.from_dict(data))
+    raise ValueError(f"Unknown variant {tag}")
+
+
+def role_to_dict(value: Role) -> Dict[str, Any]:
+    if isinstance(value, RoleAdminVariant):
+        return {"type": "admin", **value.value.to_dict()}
+    if isinstance(value, RoleOwner):
+        return {"type": "owner", **value.value.to_dict()}
+    if isinstance(value, RoleNone):
+        return {"type": "none", **value.value.to_dict()}
+    raise ValueError(f"Unknown variant {value}")
+
+
+@dataclass
+class Holder:
+    role: Role
+    lambda_: int
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "Holder":
+        return cls(
+            role=role_from_dict(data["role"]),
+            lambda_=data["lambda"],
+        )
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "role": role_to_dict(self.role),
+            "lambda": self.lambda_,
+        }
+
+
+SCALE: float = 1.0
+NONE: bool = False
+"#
+        );
+        let options = PythonOptions {
+            classes: PythonClasses::TypedDict,
+            ..Default::default()
+        };
+        assert_eq!(
+            generate(&spec, &options),
+            r#"from typing import Any, List, Literal, NoReturn, TypedDict, Union
+
+
+User = TypedDict("User", {
+    "class": str,
+    "from": "List[User]",
+    "ratio": float,
+})
+
+
+RoleAdmin = TypedDict("RoleAdmin", {})
+
+
+RoleAdminVariant = TypedDict("RoleAdminVariant", {
+    "type": Literal["admin"],
+    "class": str,
+    "from": List[User],
+    "ratio": float,
+})
+
+
+RoleOwner = TypedDict("RoleOwner", {
+    "type": Literal["owner"],
+    "class": str,
+    "from": List[User],
+    "ratio": float,
+})
+
+
+RoleNone = TypedDict("RoleNone", {
+    "type": Literal["none"],
+})
+
+
+Role = Union[RoleAdminVariant, RoleOwner, RoleNone]
+
+
+Holder = TypedDict("Holder", {
+    "role": Role,
+    "lambda": int,
+})
+
+
+SCALE: float = 1.0
+NONE: bool = False
+"#
+        );
+    }
+
+    #[test]
+    fn test_value_variants() {
+        // values of variants which are not structs are in `value` field
+        let data = "
+            .struct node
+                name: str
+            .enum tree[t]
+                leaf: t
+                branch: list[tree[t]]
+                node: node
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        let generated = generate(&spec, &PythonOptions::default());
+        assert_eq!(
+            generated,
+            r#"from dataclasses import dataclass
+from typing import Any, Callable, Dict, Generic, List, NoReturn, TypeVar, Union
+
+
+T = TypeVar("T")
+
+
+@dataclass
+class Node:
+    name: str
+
+    @classmethod
+    def from_dict(cls, data: Dict[str, Any]) -> "Node":
+        return cls(
+            name=data["name"],
+        )
+
+    def to_dict(self) -> Dict[str, Any]:
+        return {
+            "name": self.name,
+        }
+
+
+@dataclass
+class TreeLeaf(Generic[T]):
+    value: T
+
+
+@dataclass
+class TreeBranch(Generic[T]):
+    value: "List[Tree[T]]"
+
+
+@dataclass
+class TreeNode(Generic[T]):
+    value: Node
+
+
+Tree = Union[TreeLeaf[T], TreeBranch[T], TreeNode[T]]
+
+
+def tree_from_dict(data: Dict[str, Any], from_t: Callable[[Any], Any]) -> Tree:
+    tag = data["type"]
+    if tag == "leaf":
+        return TreeLeaf(from_t(data["value"]))
+    if tag == "branch":
+        return TreeBranch([tree_from_dict(v0, lambda v1: from_t(v1)) for v0 in data["value"]])
+    if tag == "node":
+        return TreeNode(Node.from_dict(data))
+    raise ValueError(f"Unknown variant {tag}")
+
+
+def tree_to_dict(value: Tree, to_t: Callable[[Any], Any]) -> Dict[str, Any]:
+    if isinstance(value, TreeLeaf):
+        return {"type": "leaf", "value": to_t(value.value)}
+    if isinstance(value, TreeBranch):
+        return {"type": "branch", "value": [tree_to_dict(v0, lambda v1: to_t(v1)) for v0 in value.value]}
+    if isinstance(value, TreeNode):
+        return {"type": "node", **value.value.to_dict()}
+    raise ValueError(f"Unknown variant {value}")
+"#
+        );
+        // generated code round-trips values through dicts, if python is available
+        let script = format!(
+            "{}\n{}",
+            generated,
+            r#"import json
+tree = TreeBranch([TreeLeaf(1), TreeNode(Node("a")), TreeBranch([])])
+data = json.loads(json.dumps(tree_to_dict(tree, lambda v: v)))
+assert data == {"type": "branch", "value": [{"type": "leaf", "value": 1}, {"type": "node", "name": "a"}, {"type": "branch", "value": []}]}, data
+assert tree_from_dict(data, lambda v: v) == tree
+"#
+        );
+        let source = std::env::temp_dir().join("leap_test_python_value_variants.py");
+        std::fs::write(&source, script).unwrap();
+        if let Ok(output) = std::process::Command::new("python3").arg(&source).output() {
+            assert!(
+                output.status.success(),
+                "{}",
+                std::string::String::from_utf8_lossy(&output.stderr)
+            );
+        }
+        let options = PythonOptions {
+            classes: PythonClasses::TypedDict,
+            ..Default::default()
+        };
+        assert_eq!(
+            generate(&spec, &options),
+            r#"from typing import Any, List, Literal, NoReturn, TypedDict, Union
+
+
+Node = TypedDict("Node", {
+    "name": str,
+})
+
+
+TreeLeaf = TypedDict("TreeLeaf", {
+    "type": Literal["leaf"],
+    "value": Any,
+})
+
+
+TreeBranch = TypedDict("TreeBranch", {
+    "type": Literal["branch"],
+    "value": "List[Tree]",
+})
+
+
+TreeNode = TypedDict("TreeNode", {
+    "type": Literal["node"],
+    "name": str,
+})
+
+
+Tree = Union[TreeLeaf, TreeBranch, TreeNode]
+"#
+        );
+    }
+}
@@ -0,0 +1,524 @@
+use super::doc_lines;
+use crate::leaptypes::{
+    LeapConst, LeapEnum, LeapSpec, LeapStruct, LeapType, Literal, Name, Prop, SimpleType, ValueType,
+};
+use crate::naming::WritingStyle;
+
+const INDENT: &str = "    ";
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RustOptions {
+    // derive `Serialize` and `Deserialize`, fields and variants are renamed to the names from spec
+    pub serde: bool,
+    // field with variant name in serialized enum, fields of struct variants are next to the tag,
+    // other variants keep their value in `value` field
+    pub enum_tag: String,
+    pub derives: Vec<String>,
+}
+
+impl Default for RustOptions {
+    fn default() -> Self {
+        RustOptions {
+            serde: false,
+            enum_tag: "type".to_owned(),
+            derives: vec![
+                "Debug".to_owned(),
+                "Clone".to_owned(),
+                "PartialEq".to_owned(),
+            ],
+        }
+    }
+}
+
+// `decimal`, `date`, `datetime`, `duration` and `uuid` are kept as strings to avoid dependencies
+pub fn generate(spec: &LeapSpec, options: &RustOptions) -> String {
+    let mut items = vec![];
+    if options.serde {
+        items.push("use serde::{Deserialize, Serialize};\n".to_owned());
+    }
+    for leap_type in spec.iter_type_refs() {
+        items.push(match leap_type {
+            LeapType::Struct(s) => generate_struct(s, spec, options),
+            LeapType::Enum(e) => generate_enum(e, spec, options),
+        });
+    }
+    items.extend(spec.iter_consts().filter_map(generate_const));
+    items.join("\n")
+}
+
+fn generate_struct(leap_struct: &LeapStruct, spec: &LeapSpec, options: &RustOptions) -> String {
+    let mut text = doc_lines(leap_struct.doc.as_deref(), "", "/// ");
+    text.push_str(&derives(options));
+    text.push_str(&format!(
+        "pub struct {}{} {{",
+        type_name(&leap_struct.name),
+        type_params(&leap_struct.args)
+    ));
+    let unused = unused_params(&leap_struct.args, &leap_struct.props);
+    if !leap_struct.props.is_empty() || !unused.is_empty() {
+        text.push('\n');
+    }
+    for prop in &leap_struct.props {
+        let name = field_name(&prop.name);
+        text.push_str(&doc_lines(prop.doc.as_deref(), INDENT, "/// "));
+        if options.serde && name.trim_start_matches("r#") != prop.name.get() {
+            text.push_str(&format!(
+                "{}#[serde(rename = \"{}\")]\n",
+                INDENT,
+                prop.name.get()
+            ));
+        }
+        text.push_str(&format!(
+            "{}pub {}: {},\n",
+            INDENT,
+            name,
+            prop_type(prop, spec)
+        ));
+    }
+    // unused type params are kept to match number of type args in spec
+    for arg in unused {
+        if options.serde {
+            text.push_str(&format!("{}#[serde(skip)]\n", INDENT));
+        }
+        text.push_str(&format!(
+            "{}pub _{}: std::marker::PhantomData<{}>,\n",
+            INDENT,
+            arg.apply_style(WritingStyle::Lower, "_"),
+            type_name(arg)
+        ));
+    }
+    text.push_str("}\n");
+    text
+}
+
+fn generate_enum(leap_enum: &LeapEnum, spec: &LeapSpec, options: &RustOptions) -> String {
+    let mut text = doc_lines(leap_enum.doc.as_deref(), "", "/// ");
+    text.push_str(&derives(options));
+    if options.serde {
+        text.push_str(&format!("#[serde(tag = \"{}\")]\n", options.enum_tag));
+    }
+    text.push_str(&format!(
+        "pub enum {}{} {{",
+        type_name(&leap_enum.name),
+        type_params(&leap_enum.args)
+    ));
+    let unused = unused_params(&leap_enum.args, &leap_enum.variants);
+    if !leap_enum.variants.is_empty() || !unused.is_empty() {
+        text.push('\n');
+    }
+    for variant in &leap_enum.variants {
+        text.push_str(&doc_lines(variant.doc.as_deref(), INDENT, "/// "));
+        if options.serde {
+            text.push_str(&format!(
+                "{}#[serde(rename = \"{}\")]\n",
+                INDENT,
+                variant.name.get()
+            ));
+        }
+        // internally tagged newtype variant can hold only a struct
+        let is_struct = matches!(&variant.prop_type, ValueType::LeapType { name, .. }
+            if spec.get_type_by_name(name.get()).is_some_and(|t| t.is_struct()));
+        if options.serde && !is_struct {
+            text.push_str(&format!(
+                "{}{} {{ value: {} }},\n",
+                INDENT,
+                type_name(&variant.name),
+                prop_type(variant, spec)
+            ));
+        } else {
+            text.push_str(&format!(
+                "{}{}({}),\n",
+                INDENT,
+                type_name(&variant.name),
+                prop_type(variant, spec)
+            ));
+        }
+    }
+    if !unused.is_empty() {
+        let args: Vec<String> = unused.into_iter().map(type_name).collect();
+        text.push_str(&format!("{}#[doc(hidden)]\n", INDENT));
+        if options.serde {
+            text.push_str(&format!("{}#[serde(skip)]\n", INDENT));
+        }
+        text.push_str(&format!(
+            "{}_Unused(std::marker::PhantomData<({},)>),\n",
+            INDENT,
+            args.join(", ")
+        ));
+    }
+    text.push_str("}\n");
+    text
+}
+
+// type params of struct or enum which are not used by its props or variants
+fn unused_params<'a>(args: &'a [Name], props: &[Prop]) -> Vec<&'a Name> {
+    args.iter()
+        .filter(|a| !props.iter().any(|p| uses_param(&p.prop_type, a)))
+        .collect()
+}
+
+fn uses_param(value_type: &ValueType, param: &Name) -> bool {
+    match value_type {
+        ValueType::Simple(..) => false,
+        ValueType::List(t, _) => uses_param(t, param),
+        ValueType::TypeArg(name) => name.get() == param.get(),
+        ValueType::LeapType { args, .. } => args.iter().any(|a| uses_param(a, param)),
+    }
+}
+
+// only constants of simple types are generated
+fn generate_const(leap_const: &LeapConst) -> Option<String> {
+    let const_type = match &leap_const.value_type {
+        ValueType::Simple(SimpleType::String, _) => "&str".to_owned(),
+        ValueType::Simple(t, _) if !matches!(simple_type(t), "String" | "Vec<u8>") => {
+            simple_type(t).to_owned()
+        }
+        _ => return None,
+    };
+    let value = match (&leap_const.value, &leap_const.value_type) {
+        // integer literal is not accepted as float
+        (Literal::Integer(v), ValueType::Simple(SimpleType::Float, _)) => format!("{}.0", v),
+        (value, _) => value.to_string(),
+    };
+    let mut text = doc_lines(leap_const.doc.as_deref(), "", "/// ");
+    text.push_str(&format!(
+        "pub const {}: {} = {};\n",
+        leap_const.name.apply_style(WritingStyle::Upper, "_"),
+        const_type,
+        value
+    ));
+    Some(text)
+}
+
+fn derives(options: &RustOptions) -> String {
+    let mut derives = options.derives.clone();
+    if options.serde {
+        derives.extend(["Serialize".to_owned(), "Deserialize".to_owned()]);
+    }
+    if derives.is_empty() {
+        "".to_owned()
+    } else {
+        format!("#[derive({})]\n", derives.join(", "))
+    }
+}
+
+fn type_name(name: &Name) -> String {
+    match name.apply_style(WritingStyle::UpperCamel, "").as_str() {
+        "Self" => "Self_".to_owned(),
+        name => name.to_owned(),
+    }
+}
+
+// path to type from std if it is shadowed by type from spec (`.struct string`)
+fn std_type(spec: &LeapSpec, name: &'static str, path: &'static str) -> &'static str {
+    if spec.get_type_by_name(&name.to_lowercase()).is_some() {
+        path
+    } else {
+        name
+    }
+}
+
+fn field_name(name: &Name) -> String {
+    let name = name.apply_style(WritingStyle::Lower, "_");
+    match name.as_str() {
+        "crate" | "self" | "super" => format!("{}_", name),
+        n if KEYWORDS.contains(&n) => format!("r#{}", name),
+        _ => name,
+    }
+}
+
+fn type_params(args: &[Name]) -> String {
+    if args.is_empty() {
+        "".to_owned()
+    } else {
+        let args: Vec<String> = args.iter().map(type_name).collect();
+        format!("<{}>", args.join(", "))
+    }
+}
+
+// recursive props are boxed to have a known size
+fn prop_type(prop: &Prop, spec: &LeapSpec) -> String {
+    let text = value_type(&prop.prop_type, spec);
+    if prop.is_recursive {
+        format!("{}<{}>", std_type(spec, "Box", "std::boxed::Box"), text)
+    } else {
+        text
+    }
+}
+
+fn value_type(value_type: &ValueType, spec: &LeapSpec) -> String {
+    let vec = std_type(spec, "Vec", "std::vec::Vec");
+    match value_type {
+        ValueType::Simple(SimpleType::Bytes, _) => format!("{}<u8>", vec),
+        ValueType::Simple(t, _) => match simple_type(t) {
+            "String" => std_type(spec, "String", "std::string::String").to_owned(),
+            t => t.to_owned(),
+        },
+        ValueType::List(t, _) => format!("{}<{}>", vec, self::value_type(t, spec)),
+        ValueType::TypeArg(name) => type_name(name),
+        ValueType::LeapType { name, args, .. } => {
+            if args.is_empty() {
+                type_name(name)
+            } else {
+                let args: Vec<String> = args.iter().map(|a| self::value_type(a, spec)).collect();
+                format!("{}<{}>", type_name(name), args.join(", "))
+            }
+        }
+    }
+}
+
+fn simple_type(simple_type: &SimpleType) -> &'static str {
+    match simple_type {
+        SimpleType::Integer => "i64",
+        SimpleType::Float => "f64",
+        SimpleType::Boolean => "bool",
+        SimpleType::Int8 => "i8",
+        SimpleType::Int16 => "i16",
+        SimpleType::Int32 => "i32",
+        SimpleType::Int64 => "i64",
+        SimpleType::UInt8 => "u8",
+        SimpleType::UInt16 => "u16",
+        SimpleType::UInt32 => "u32",
+        SimpleType::UInt64 => "u64",
+        SimpleType::Bytes => "Vec<u8>",
+        SimpleType::String
+        | SimpleType::Decimal
+        | SimpleType::Date
+        | SimpleType::DateTime
+        | SimpleType::Duration
+        | SimpleType::Uuid => "String",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_generate() {
+        let data = "
+            /-- user of the service
+            .struct user
+                id: int
+                first-name: str
+                type: str /-- kind of user
+                roles: list[role]
+                manager: option[user]
+
+            .struct page[t]
+                items: list[t]
+                total-count: u32
+
+            .struct none
+            .struct some[t]
+                value: t
+            .enum option[t]
+                none
+                some[t]
+
+            .struct admin
+            .enum role
+                admin
+                guest: none
+
+            .const max-page-size: int = 100
+            .const api-version: str = \"2.3\"
+            .const default-role: role = role.admin
+        ";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        spec.mark_recursive_props();
+        assert_eq!(
+            generate(&spec, &RustOptions::default()),
+            r#"/// user of the service
+#[derive(Debug, Clone, PartialEq)]
+pub struct User {
+    pub id: i64,
+    pub first_name: String,
+    /// kind of user
+    pub r#type: String,
+    pub roles: Vec<Role>,
+    pub manager: Box<Option<User>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total_count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct None {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Some<T> {
+    pub value: T,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Option<T> {
+    None(None),
+    Some(Some<T>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Admin {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Role {
+    Admin(Admin),
+    Guest(None),
+}
+
+pub const MAX_PAGE_SIZE: i64 = 100;
+
+pub const API_VERSION: &str = "2.3";
+"#
+        );
+        let options = RustOptions {
+            serde: true,
+            derives: vec![],
+            ..Default::default()
+        };
+        let spec = LeapSpec::new(
+            Parser::parse(
+                ".struct user\n    first-name: str\n.enum role\n    admin: user\n    level: int\n.struct page[t]",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            generate(&spec, &options),
+            r#"use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+pub struct User {
+    #[serde(rename = "first-name")]
+    pub first_name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Role {
+    #[serde(rename = "admin")]
+    Admin(User),
+    #[serde(rename = "level")]
+    Level { value: i64 },
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Page<T> {
+    #[serde(skip)]
+    pub _t: std::marker::PhantomData<T>,
+}
+"#
+        );
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let data = "
+            .struct string
+                self: str
+                data: bytes
+            .struct box
+                inner: option[box]
+            .enum option[t]
+                none: string
+                some: t
+            .enum role
+                admin: string
+                owner: string
+            .struct self
+                type: list[role]
+            .struct page[t]
+                total: int
+            .enum kind[t u]
+                plain: page[u]
+            .const ratio: float = 1
+            .const scale: float = 2.5
+        ";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        spec.mark_recursive_props();
+        let generated = generate(&spec, &RustOptions::default());
+        assert_eq!(
+            generated,
+            r#"#[derive(Debug, Clone, PartialEq)]
+pub struct String {
+    pub self_: std::string::String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Box {
+    pub inner: std::boxed::Box<Option<Box>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Option<T> {
+    None(String),
+    Some(T),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Role {
+    Admin(String),
+    Owner(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Self_ {
+    pub r#type: Vec<Role>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub total: i64,
+    pub _t: std::marker::PhantomData<T>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Kind<T, U> {
+    Plain(Page<U>),
+    #[doc(hidden)]
+    _Unused(std::marker::PhantomData<(T,)>),
+}
+
+pub const RATIO: f64 = 1.0;
+
+pub const SCALE: f64 = 2.5;
+"#
+        );
+        // generated code compiles, if rustc is available
+        let dir = std::env::temp_dir();
+        let source = dir.join("leap_test_rust_edge_cases.rs");
+        std::fs::write(&source, &generated).unwrap();
+        let output = std::process::Command::new("rustc")
+            .args([
+                "--crate-type",
+                "lib",
+                "--edition",
+                "2021",
+                "--emit",
+                "metadata",
+            ])
+            .arg("--out-dir")
+            .arg(&dir)
+            .arg(&source)
+            .output();
+        if let Ok(output) = output {
+            assert!(
+                output.status.success(),
+                "{}",
+                std::string::String::from_utf8_lossy(&output.stderr)
+            );
+        }
+    }
+}
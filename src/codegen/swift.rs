@@ -0,0 +1,548 @@
+use super::doc_lines;
+use super::lowering::{self, ConstDecl, Record, TypeDecl, TypeRef, Union};
+use crate::leaptypes::{LeapSpec, Name, SimpleType};
+use crate::naming::WritingStyle;
+
+const INDENT: &str = "    ";
+
+const KEYWORDS: &[&str] = &[
+    "as",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "continue",
+    "default",
+    "defer",
+    "do",
+    "else",
+    "enum",
+    "extension",
+    "fallthrough",
+    "false",
+    "for",
+    "func",
+    "guard",
+    "if",
+    "import",
+    "in",
+    "init",
+    "inout",
+    "internal",
+    "is",
+    "let",
+    "nil",
+    "operator",
+    "private",
+    "protocol",
+    "public",
+    "repeat",
+    "rethrows",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "subscript",
+    "super",
+    "switch",
+    "throw",
+    "throws",
+    "true",
+    "try",
+    "typealias",
+    "var",
+    "where",
+    "while",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwiftOptions {
+    // field with variant name in enum values, variants are expected to be structs
+    pub enum_tag: String,
+}
+
+impl Default for SwiftOptions {
+    fn default() -> Self {
+        SwiftOptions {
+            enum_tag: "type".to_owned(),
+        }
+    }
+}
+
+// structs are `Codable` structs with coding keys of names from spec, enums have a case with
+// the variant struct for every variant and are encoded with the tag next to the struct fields;
+// `bytes` is `Data` (base64 in json), `decimal`, `date`, `datetime`, `duration` and `uuid`
+// are strings
+pub fn generate(spec: &LeapSpec, options: &SwiftOptions) -> String {
+    let module = lowering::lower(spec);
+    let mut items = vec!["import Foundation\n".to_owned()];
+    let types: Vec<String> = module
+        .types
+        .iter()
+        .map(|t| match t {
+            TypeDecl::Record(r) => type_name(&r.name),
+            TypeDecl::Union(u) => type_name(&u.name),
+        })
+        .collect();
+    for type_decl in &module.types {
+        items.push(match type_decl {
+            TypeDecl::Record(r) => generate_struct(r, &types),
+            TypeDecl::Union(u) => generate_enum(u, options, &types),
+        });
+    }
+    items.extend(module.consts.iter().map(|c| generate_const(c, &types)));
+    items.join("\n")
+}
+
+fn generate_struct(record: &Record, types: &[String]) -> String {
+    let mut text = doc_lines(record.doc.as_deref(), "", "/// ");
+    text.push_str(&format!(
+        "struct {}{}: Codable {{",
+        type_name(&record.name),
+        type_params(&record.params)
+    ));
+    if record.fields.is_empty() {
+        text.push_str("}\n");
+        return text;
+    }
+    text.push('\n');
+    for field in &record.fields {
+        text.push_str(&doc_lines(field.doc.as_deref(), INDENT, "/// "));
+        text.push_str(&format!(
+            "{}var {}: {}\n",
+            INDENT,
+            member_name(&field.name),
+            swift_type(&field.field_type, types)
+        ));
+    }
+    text.push_str(&format!(
+        "\n{}enum CodingKeys: {}, CodingKey {{\n",
+        INDENT,
+        swift_name("String", "Swift", types)
+    ));
+    for field in &record.fields {
+        let name = member_name(&field.name);
+        if name.trim_matches('`') == field.name.get() {
+            text.push_str(&format!("{}{}case {}\n", INDENT, INDENT, name));
+        } else {
+            text.push_str(&format!(
+                "{}{}case {} = \"{}\"\n",
+                INDENT,
+                INDENT,
+                name,
+                field.name.get()
+            ));
+        }
+    }
+    text.push_str(&format!("{}}}\n}}\n", INDENT));
+    text
+}
+
+// enums are indirect, so recursive types through enums have a known size
+fn generate_enum(union: &Union, options: &SwiftOptions, types: &[String]) -> String {
+    let name = type_name(&union.name);
+    let string = swift_name("String", "Swift", types);
+    let indent2 = INDENT.repeat(2);
+    let indent3 = INDENT.repeat(3);
+    let mut text = doc_lines(union.doc.as_deref(), "", "/// ");
+    // enums without cases can't be indirect
+    if !union.variants.is_empty() {
+        text.push_str("indirect ");
+    }
+    text.push_str(&format!(
+        "enum {}{}: Codable {{\n",
+        name,
+        type_params(&union.params)
+    ));
+    for variant in &union.variants {
+        text.push_str(&doc_lines(variant.doc.as_deref(), INDENT, "/// "));
+        text.push_str(&format!(
+            "{}case {}({})\n",
+            INDENT,
+            member_name(&variant.name),
+            swift_type(&variant.payload, types)
+        ));
+    }
+    if !union.variants.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(&format!(
+        "{}private enum TagKeys: {}, CodingKey {{\n{}case tag = \"{}\"\n{}}}\n\n",
+        INDENT, string, indent2, options.enum_tag, INDENT
+    ));
+    text.push_str(&format!(
+        "{}init(from decoder: Decoder) throws {{\n{}let container = try decoder.container(keyedBy: TagKeys.self)\n{}switch try container.decode({}.self, forKey: .tag) {{\n",
+        INDENT, indent2, indent2, string
+    ));
+    for variant in &union.variants {
+        text.push_str(&format!(
+            "{}case \"{}\":\n{}self = .{}(try {}(from: decoder))\n",
+            indent2,
+            variant.name.get(),
+            indent3,
+            member_name(&variant.name),
+            swift_type(&variant.payload, types)
+        ));
+    }
+    text.push_str(&format!(
+        "{}case let tag:\n{}throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: \"Unknown variant \\(tag)\")\n{}}}\n{}}}\n\n",
+        indent2, indent3, indent2, INDENT
+    ));
+    text.push_str(&format!(
+        "{}func encode(to encoder: Encoder) throws {{\n",
+        INDENT
+    ));
+    if !union.variants.is_empty() {
+        text.push_str(&format!(
+            "{}var container = encoder.container(keyedBy: TagKeys.self)\n",
+            indent2
+        ));
+    }
+    text.push_str(&format!("{}switch self {{\n", indent2));
+    for variant in &union.variants {
+        text.push_str(&format!(
+            "{}case .{}(let value):\n{}try container.encode(\"{}\", forKey: .tag)\n{}try value.encode(to: encoder)\n",
+            indent2,
+            member_name(&variant.name),
+            indent3,
+            variant.name.get(),
+            indent3
+        ));
+    }
+    text.push_str(&format!("{}}}\n{}}}\n}}\n", indent2, INDENT));
+    text
+}
+
+fn generate_const(const_decl: &ConstDecl, types: &[String]) -> String {
+    let mut text = doc_lines(const_decl.doc.as_deref(), "", "/// ");
+    let const_type = match const_decl.const_type {
+        // `Data` has no literals
+        SimpleType::Bytes => "String",
+        ref t => simple_type(t),
+    };
+    text.push_str(&format!(
+        "let {}: {} = {}\n",
+        member_name(&const_decl.name),
+        swift_name(const_type, "Swift", types),
+        const_decl.value
+    ));
+    text
+}
+
+fn type_name(name: &Name) -> String {
+    name.apply_style(WritingStyle::UpperCamel, "")
+}
+
+fn member_name(name: &Name) -> String {
+    let name = name.apply_style(WritingStyle::LowerCamel, "");
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("`{}`", name)
+    } else {
+        name
+    }
+}
+
+fn type_params(params: &[Name]) -> String {
+    if params.is_empty() {
+        "".to_owned()
+    } else {
+        let params: Vec<String> = params
+            .iter()
+            .map(|p| format!("{}: Codable", type_name(p)))
+            .collect();
+        format!("<{}>", params.join(", "))
+    }
+}
+
+fn swift_type(type_ref: &TypeRef, types: &[String]) -> String {
+    match type_ref {
+        TypeRef::Simple(SimpleType::Bytes) => swift_name("Data", "Foundation", types),
+        TypeRef::Simple(t) => swift_name(simple_type(t), "Swift", types),
+        TypeRef::List(t) => format!("[{}]", swift_type(t, types)),
+        TypeRef::Param(name) => type_name(name),
+        TypeRef::Record { name, args } | TypeRef::Union { name, args } => {
+            if args.is_empty() {
+                type_name(name)
+            } else {
+                let args: Vec<String> = args.iter().map(|a| swift_type(a, types)).collect();
+                format!("{}<{}>", type_name(name), args.join(", "))
+            }
+        }
+    }
+}
+
+// standard type qualified with its module if a type of the spec hides it, eg. `Swift.String`
+fn swift_name(name: &str, module: &str, types: &[String]) -> String {
+    let name = name.to_owned();
+    if types.contains(&name) {
+        format!("{}.{}", module, name)
+    } else {
+        name
+    }
+}
+
+fn simple_type(simple_type: &SimpleType) -> &'static str {
+    match simple_type {
+        SimpleType::Integer => "Int64",
+        SimpleType::Float => "Double",
+        SimpleType::Boolean => "Bool",
+        SimpleType::Int8 => "Int8",
+        SimpleType::Int16 => "Int16",
+        SimpleType::Int32 => "Int32",
+        SimpleType::Int64 => "Int64",
+        SimpleType::UInt8 => "UInt8",
+        SimpleType::UInt16 => "UInt16",
+        SimpleType::UInt32 => "UInt32",
+        SimpleType::UInt64 => "UInt64",
+        SimpleType::Bytes => "Data",
+        SimpleType::String
+        | SimpleType::Decimal
+        | SimpleType::Date
+        | SimpleType::DateTime
+        | SimpleType::Duration
+        | SimpleType::Uuid => "String",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_generate() {
+        let data = "
+            /-- user of the service
+            .struct user
+                id: int
+                first-name: str /-- given name
+                default: bool
+                roles: list[role]
+                manager: option[user]
+
+            .struct none
+            .struct some[t]
+                value: t
+            .enum option[t]
+                none
+                some[t]
+            .enum empty
+
+            .enum role
+                /-- full access
+                admin: user
+                guest: none
+
+            .const max-page-size: u32 = 100
+            .const api-version: str = \"2.3\"
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &SwiftOptions::default()),
+            r#"import Foundation
+
+/// user of the service
+struct User: Codable {
+    var id: Int64
+    /// given name
+    var firstName: String
+    var `default`: Bool
+    var roles: [Role]
+    var manager: Option<User>
+
+    enum CodingKeys: String, CodingKey {
+        case id
+        case firstName = "first-name"
+        case `default`
+        case roles
+        case manager
+    }
+}
+
+struct None: Codable {}
+
+struct Some<T: Codable>: Codable {
+    var value: T
+
+    enum CodingKeys: String, CodingKey {
+        case value
+    }
+}
+
+indirect enum Option<T: Codable>: Codable {
+    case none(None)
+    case some(Some<T>)
+
+    private enum TagKeys: String, CodingKey {
+        case tag = "type"
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: TagKeys.self)
+        switch try container.decode(String.self, forKey: .tag) {
+        case "none":
+            self = .none(try None(from: decoder))
+        case "some":
+            self = .some(try Some<T>(from: decoder))
+        case let tag:
+            throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "Unknown variant \(tag)")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: TagKeys.self)
+        switch self {
+        case .none(let value):
+            try container.encode("none", forKey: .tag)
+            try value.encode(to: encoder)
+        case .some(let value):
+            try container.encode("some", forKey: .tag)
+            try value.encode(to: encoder)
+        }
+    }
+}
+
+enum Empty: Codable {
+    private enum TagKeys: String, CodingKey {
+        case tag = "type"
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: TagKeys.self)
+        switch try container.decode(String.self, forKey: .tag) {
+        case let tag:
+            throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "Unknown variant \(tag)")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        switch self {
+        }
+    }
+}
+
+indirect enum Role: Codable {
+    /// full access
+    case admin(User)
+    case guest(None)
+
+    private enum TagKeys: String, CodingKey {
+        case tag = "type"
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: TagKeys.self)
+        switch try container.decode(String.self, forKey: .tag) {
+        case "admin":
+            self = .admin(try User(from: decoder))
+        case "guest":
+            self = .guest(try None(from: decoder))
+        case let tag:
+            throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "Unknown variant \(tag)")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: TagKeys.self)
+        switch self {
+        case .admin(let value):
+            try container.encode("admin", forKey: .tag)
+            try value.encode(to: encoder)
+        case .guest(let value):
+            try container.encode("guest", forKey: .tag)
+            try value.encode(to: encoder)
+        }
+    }
+}
+
+let maxPageSize: UInt32 = 100
+
+let apiVersion: String = "2.3"
+"#
+        );
+    }
+    #[test]
+    fn test_edge_cases() {
+        // variants with the same type, names which are swift keywords or hide standard types
+        let data = "
+            .struct string
+                value: str
+                data: bytes
+            .struct data
+                func: list[string]
+            .enum role
+                admin: string
+                owner: string
+                default: data
+            .const ratio: float = 1
+            .const title: str = \"admin\"
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &SwiftOptions::default()),
+            r#"import Foundation
+
+struct String: Codable {
+    var value: Swift.String
+    var data: Foundation.Data
+
+    enum CodingKeys: Swift.String, CodingKey {
+        case value
+        case data
+    }
+}
+
+struct Data: Codable {
+    var `func`: [String]
+
+    enum CodingKeys: Swift.String, CodingKey {
+        case `func`
+    }
+}
+
+indirect enum Role: Codable {
+    case admin(String)
+    case owner(String)
+    case `default`(Data)
+
+    private enum TagKeys: Swift.String, CodingKey {
+        case tag = "type"
+    }
+
+    init(from decoder: Decoder) throws {
+        let container = try decoder.container(keyedBy: TagKeys.self)
+        switch try container.decode(Swift.String.self, forKey: .tag) {
+        case "admin":
+            self = .admin(try String(from: decoder))
+        case "owner":
+            self = .owner(try String(from: decoder))
+        case "default":
+            self = .`default`(try Data(from: decoder))
+        case let tag:
+            throw DecodingError.dataCorruptedError(forKey: .tag, in: container, debugDescription: "Unknown variant \(tag)")
+        }
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: TagKeys.self)
+        switch self {
+        case .admin(let value):
+            try container.encode("admin", forKey: .tag)
+            try value.encode(to: encoder)
+        case .owner(let value):
+            try container.encode("owner", forKey: .tag)
+            try value.encode(to: encoder)
+        case .`default`(let value):
+            try container.encode("default", forKey: .tag)
+            try value.encode(to: encoder)
+        }
+    }
+}
+
+let ratio: Double = 1
+
+let title: Swift.String = "admin"
+"#
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use crate::leaptypes::{
+    LeapConst, LeapEnum, LeapSpec, LeapStruct, LeapType, Name, SimpleType, ValueType,
+};
+use crate::naming::WritingStyle;
+
+const INDENT: &str = "    ";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeScriptOptions {
+    // field with variant name in enum values, eg. `{ type: "admin", ... }`
+    pub enum_tag: String,
+    // use names of props from spec (eg. `"first-name"`) instead of lower camel case (eg. `firstName`)
+    pub keep_prop_names: bool,
+}
+
+impl Default for TypeScriptOptions {
+    fn default() -> Self {
+        TypeScriptOptions {
+            enum_tag: "type".to_owned(),
+            keep_prop_names: false,
+        }
+    }
+}
+
+// all numbers are `number`, `bytes` (base64), `decimal`, `date`, `datetime`, `duration`
+// and `uuid` are strings
+pub fn generate(spec: &LeapSpec, options: &TypeScriptOptions) -> String {
+    let mut items = vec![];
+    for leap_type in spec.iter_type_refs() {
+        items.push(match leap_type {
+            LeapType::Struct(s) => generate_interface(s, spec, options),
+            LeapType::Enum(e) => generate_union(e, spec, options),
+        });
+    }
+    items.extend(spec.iter_consts().filter_map(generate_const));
+    items.join("\n")
+}
+
+fn generate_interface(
+    leap_struct: &LeapStruct,
+    spec: &LeapSpec,
+    options: &TypeScriptOptions,
+) -> String {
+    let mut text = js_doc(leap_struct.doc.as_deref(), "");
+    text.push_str(&format!(
+        "export interface {}{} {{",
+        type_name(&leap_struct.name),
+        type_params(&leap_struct.args)
+    ));
+    if !leap_struct.props.is_empty() {
+        text.push('\n');
+    }
+    for prop in &leap_struct.props {
+        text.push_str(&js_doc(prop.doc.as_deref(), INDENT));
+        text.push_str(&format!(
+            "{}{}: {};\n",
+            INDENT,
+            prop_name(&prop.name, options),
+            value_type(&prop.prop_type, spec)
+        ));
+    }
+    text.push_str("}\n");
+    text
+}
+
+// discriminated union, tag is added to fields of the variant struct, payload of other type is
+// kept in `value` field
+fn generate_union(leap_enum: &LeapEnum, spec: &LeapSpec, options: &TypeScriptOptions) -> String {
+    let mut text = js_doc(leap_enum.doc.as_deref(), "");
+    text.push_str(&format!(
+        "export type {}{} =",
+        type_name(&leap_enum.name),
+        type_params(&leap_enum.args)
+    ));
+    if leap_enum.variants.is_empty() {
+        text.push_str(" never");
+    }
+    for variant in &leap_enum.variants {
+        text.push('\n');
+        text.push_str(&js_doc(variant.doc.as_deref(), INDENT));
+        let is_struct = matches!(&variant.prop_type, ValueType::LeapType { name, .. }
+            if spec.get_type_by_name(name.get()).is_some_and(|t| t.is_struct()));
+        if is_struct {
+            text.push_str(&format!(
+                "{}| ({{ {}: \"{}\" }} & {})",
+                INDENT,
+                options.enum_tag,
+                variant.name.get(),
+                value_type(&variant.prop_type, spec)
+            ));
+        } else {
+            text.push_str(&format!(
+                "{}| {{ {}: \"{}\"; value: {} }}",
+                INDENT,
+                options.enum_tag,
+                variant.name.get(),
+                value_type(&variant.prop_type, spec)
+            ));
+        }
+    }
+    text.push_str(";\n");
+    text
+}
+
+// only constants of simple types are generated
+fn generate_const(leap_const: &LeapConst) -> Option<String> {
+    let ValueType::Simple(t, _) = &leap_const.value_type else {
+        return None;
+    };
+    let mut text = js_doc(leap_const.doc.as_deref(), "");
+    text.push_str(&format!(
+        "export const {}: {} = {};\n",
+        leap_const.name.apply_style(WritingStyle::Upper, "_"),
+        simple_type(t),
+        leap_const.value
+    ));
+    Some(text)
+}
+
+fn js_doc(doc: Option<&str>, indent: &str) -> String {
+    match doc.map(|d| d.split('\n').collect::<Vec<_>>()) {
+        None => "".to_owned(),
+        Some(lines) if lines.len() == 1 => format!("{}/** {} */\n", indent, lines[0]),
+        Some(lines) => {
+            let mut text = format!("{}/**\n", indent);
+            for line in lines {
+                text.push_str(&format!("{} * {}\n", indent, line).replace(" \n", "\n"));
+            }
+            text.push_str(&format!("{} */\n", indent));
+            text
+        }
+    }
+}
+
+fn type_name(name: &Name) -> String {
+    name.apply_style(WritingStyle::UpperCamel, "")
+}
+
+fn prop_name(name: &Name, options: &TypeScriptOptions) -> String {
+    if !options.keep_prop_names {
+        name.apply_style(WritingStyle::LowerCamel, "")
+    } else if name.get().contains('-') {
+        format!("\"{}\"", name.get())
+    } else {
+        name.get().to_owned()
+    }
+}
+
+fn type_params(args: &[Name]) -> String {
+    if args.is_empty() {
+        "".to_owned()
+    } else {
+        let args: Vec<String> = args.iter().map(type_name).collect();
+        format!("<{}>", args.join(", "))
+    }
+}
+
+fn value_type(value_type: &ValueType, spec: &LeapSpec) -> String {
+    match value_type {
+        ValueType::Simple(t, _) => simple_type(t).to_owned(),
+        // global `Array` is shadowed by `.struct array`
+        ValueType::List(t, _) if spec.get_type_by_name("array").is_some() => {
+            format!("{}[]", self::value_type(t, spec))
+        }
+        ValueType::List(t, _) => format!("Array<{}>", self::value_type(t, spec)),
+        ValueType::TypeArg(name) => type_name(name),
+        ValueType::LeapType { name, args, .. } => {
+            if args.is_empty() {
+                type_name(name)
+            } else {
+                let args: Vec<String> = args.iter().map(|a| self::value_type(a, spec)).collect();
+                format!("{}<{}>", type_name(name), args.join(", "))
+            }
+        }
+    }
+}
+
+fn simple_type(simple_type: &SimpleType) -> &'static str {
+    match simple_type {
+        SimpleType::Boolean => "boolean",
+        SimpleType::Integer
+        | SimpleType::Float
+        | SimpleType::Int8
+        | SimpleType::Int16
+        | SimpleType::Int32
+        | SimpleType::Int64
+        | SimpleType::UInt8
+        | SimpleType::UInt16
+        | SimpleType::UInt32
+        | SimpleType::UInt64 => "number",
+        SimpleType::String
+        | SimpleType::Bytes
+        | SimpleType::Decimal
+        | SimpleType::Date
+        | SimpleType::DateTime
+        | SimpleType::Duration
+        | SimpleType::Uuid => "string",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_generate() {
+        let data = "
+            /-- user of the service
+            /-- second line
+            .struct user
+                id: int
+                first-name: str /-- given name
+                roles: list[role]
+                created: datetime
+
+            .struct page[t]
+                items: list[t]
+
+            .struct none
+            .struct some[t]
+                value: t
+            .enum option[t]
+                none
+                /-- has value
+                some[t]
+            .enum empty
+
+            .enum role
+                admin: user
+                guest: none
+
+            .const max-page-size: int = 100
+            .const api-version: str = \"2.3\"
+            .const default-role: role = role.guest
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        assert_eq!(
+            generate(&spec, &TypeScriptOptions::default()),
+            r#"/**
+ * user of the service
+ * second line
+ */
+export interface User {
+    id: number;
+    /** given name */
+    firstName: string;
+    roles: Array<Role>;
+    created: string;
+}
+
+export interface Page<T> {
+    items: Array<T>;
+}
+
+export interface None {}
+
+export interface Some<T> {
+    value: T;
+}
+
+export type Option<T> =
+    | ({ type: "none" } & None)
+    /** has value */
+    | ({ type: "some" } & Some<T>);
+
+export type Empty = never;
+
+export type Role =
+    | ({ type: "admin" } & User)
+    | ({ type: "guest" } & None);
+
+export const MAX_PAGE_SIZE: number = 100;
+
+export const API_VERSION: string = "2.3";
+"#
+        );
+        let options = TypeScriptOptions {
+            enum_tag: "kind".to_owned(),
+            keep_prop_names: true,
+        };
+        let spec = LeapSpec::new(
+            Parser::parse(
+                ".struct user\n    first-name: str\n    id: int\n.enum role\n    admin: user",
+            )
+            .unwrap(),
+        );
+        assert_eq!(
+            generate(&spec, &options),
+            "export interface User {\n    \"first-name\": string;\n    id: number;\n}\n\nexport type Role =\n    | ({ kind: \"admin\" } & User);\n"
+        );
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let data = "
+            .struct array
+                items: list[list[user]]
+            .struct user
+                type: str
+                delete: bool
+                first-name: str
+            .enum role
+                admin: user
+                owner: user
+            .enum tree[t]
+                leaf: t
+                branch: list[tree[t]]
+                role: role
+            .const ratio: float = 1
+        ";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        let options = TypeScriptOptions {
+            keep_prop_names: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            generate(&spec, &options),
+            r#"export interface Array {
+    items: User[][];
+}
+
+export interface User {
+    type: string;
+    delete: boolean;
+    "first-name": string;
+}
+
+export type Role =
+    | ({ type: "admin" } & User)
+    | ({ type: "owner" } & User);
+
+export type Tree<T> =
+    | { type: "leaf"; value: T }
+    | { type: "branch"; value: Tree<T>[] }
+    | { type: "role"; value: Role };
+
+export const RATIO: number = 1;
+"#
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    leaptypes::{LeapDefinition, LeapType},
+    parser::{cst, itemposition::ItemPosition, parser::Parser, position::Position},
+    textedit::TextEdit,
+    visitor::VisitorMut,
+};
+
+// top level block of document, positions of definitions and error are absolute
+struct Block {
+    position: Position,
+    result: Result<Vec<LeapDefinition>, ItemPosition<String>>,
+}
+
+// parsed document, which can be updated by text edits, only blocks touched by edit are parsed again
+pub struct Document {
+    path: String,
+    data: String,
+    blocks: Vec<Block>,
+}
+
+// definitions and errors of blocks which were parsed again after edit
+pub struct Reparsed<'a> {
+    pub definitions: Vec<&'a LeapDefinition>,
+    pub diagnostics: Vec<&'a ItemPosition<String>>,
+}
+
+impl Document {
+    pub fn new(path: &str, data: &str) -> Self {
+        let mut document = Document {
+            path: path.to_owned(),
+            data: data.to_owned(),
+            blocks: vec![],
+        };
+        document.blocks = document.parse_blocks(0, data);
+        document
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = &LeapDefinition> {
+        self.blocks
+            .iter()
+            .filter_map(|b| b.result.as_ref().ok())
+            .flatten()
+    }
+
+    pub fn types(&self) -> impl Iterator<Item = &LeapType> {
+        self.definitions().filter_map(|d| d.as_type())
+    }
+
+    pub fn diagnostics(&self) -> impl Iterator<Item = &ItemPosition<String>> {
+        self.blocks.iter().filter_map(|b| b.result.as_ref().err())
+    }
+
+    pub fn edit(&mut self, edit: &TextEdit) -> Reparsed<'_> {
+        self.data = edit.apply(&self.data);
+        let delta = edit.delta();
+        // block containing edit start and block containing edit end, neighbour blocks are
+        // included too, as edit can move boundaries of blocks (eg. remove `.struct` keyword)
+        let first = self.block_index(edit.position.start).saturating_sub(1);
+        let last = (self.block_index(edit.position.end()) + 1).min(self.blocks.len() - 1);
+        let start = self.blocks[first].position.start;
+        let end = shift(self.blocks[last].position.end(), delta);
+        let data: String = self.data.chars().skip(start).take(end - start).collect();
+        let blocks = self.parse_blocks(start, &data);
+        for block in &mut self.blocks[last + 1..] {
+            shift_block(block, delta);
+        }
+        let count = blocks.len();
+        self.blocks.splice(first..=last, blocks);
+        let reparsed = &self.blocks[first..first + count];
+        Reparsed {
+            definitions: reparsed
+                .iter()
+                .filter_map(|b| b.result.as_ref().ok())
+                .flatten()
+                .collect(),
+            diagnostics: reparsed
+                .iter()
+                .filter_map(|b| b.result.as_ref().err())
+                .collect(),
+        }
+    }
+
+    // index of the last block starting at or before position
+    fn block_index(&self, position: usize) -> usize {
+        self.blocks
+            .iter()
+            .rposition(|b| b.position.start <= position)
+            .unwrap_or(0)
+    }
+
+    fn parse_blocks(&self, offset: usize, data: &str) -> Vec<Block> {
+        cst::split_blocks(data)
+            .into_iter()
+            .map(|p| {
+                let text: String = data.chars().skip(p.start).take(p.length).collect();
+                let mut block = Block {
+                    position: Position::new(0, p.length),
+                    result: Parser::parse_definitions(&text),
+                };
+                shift_block(&mut block, (offset + p.start) as isize);
+                if let Ok(definitions) = &mut block.result {
+                    for d in definitions {
+                        d.set_path(self.path.clone());
+                    }
+                }
+                block
+            })
+            .collect()
+    }
+}
+
+fn shift(index: usize, delta: isize) -> usize {
+    index.saturating_add_signed(delta)
+}
+
+fn shift_position(position: &mut Position, delta: isize) {
+    position.start = shift(position.start, delta);
+}
+
+fn shift_block(block: &mut Block, delta: isize) {
+    shift_position(&mut block.position, delta);
+    match &mut block.result {
+        Ok(definitions) => definitions
+            .iter_mut()
+            .for_each(|d| Shift(delta).visit_definition_mut(d)),
+        Err(error) => shift_position(&mut error.0, delta),
+    }
+}
+
+// moves all positions of definition
+struct Shift(isize);
+
+impl VisitorMut for Shift {
+    fn visit_position_mut(&mut self, position: &mut Position) {
+        shift_position(position, self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_names(document: &Document) -> Vec<String> {
+        document
+            .types()
+            .map(|t| t.name().get().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_edit() {
+        let data = ".struct a\n    x: int\n\n.struct b\n    y: a\n\n.enum c\n    b\n";
+        let mut document = Document::new("a.leap", data);
+        assert_eq!(type_names(&document), vec!["a", "b", "c"]);
+        // rename `b` to `bb`, only blocks around edit are parsed
+        let edit = TextEdit::new(Position::new(30, 1), "bb".to_owned());
+        let reparsed = document.edit(&edit);
+
+        assert_eq!(reparsed.definitions.len(), 3);
+        assert!(reparsed.diagnostics.is_empty());
+        assert_eq!(type_names(&document), vec!["a", "bb", "c"]);
+        let c = document.types().last().unwrap();
+        assert_eq!(c.name().position, Position::new(49, 1));
+        assert_eq!(c.path(), "a.leap");
+        // positions are same as in full parse
+        let mut full = Parser::parse(document.data()).unwrap();
+        full.iter_mut()
+            .for_each(|t| t.set_path("a.leap".to_owned()));
+        let types: Vec<&LeapType> = document.types().collect();
+        assert_eq!(format!("{:?}", types), format!("{:?}", full));
+    }
+
+    #[test]
+    fn test_edit_errors() {
+        let data =
+            ".struct a\n    x: int\n\n.struct b\n    y: a\n\n.struct c\n    z: b\n\n.struct d\n";
+        let mut document = Document::new("", data);
+        // break the last block
+        let edit = TextEdit::new(Position::new(72, 0), "    z:\n".to_owned());
+        let reparsed = document.edit(&edit);
+        assert_eq!(reparsed.diagnostics.len(), 1);
+        assert_eq!(type_names(&document), vec!["a", "b", "c"]);
+        // remove keyword of `c`, its props join `b`
+        let edit = TextEdit::new(Position::new(42, 10), "".to_owned());
+        document.edit(&edit);
+        assert_eq!(type_names(&document), vec!["a", "b"]);
+        let b = document.types().last().unwrap().as_struct().unwrap();
+        assert_eq!(b.props.len(), 2);
+        let errors: Vec<_> = document.diagnostics().collect();
+        assert_eq!(
+            *errors[0],
+            ItemPosition::new(68, 0, "Expecting name".to_owned())
+        );
+        assert_eq!(
+            format!("{:?}", Parser::parse(document.data()).err().unwrap()),
+            format!("{:?}", errors[0])
+        );
+    }
+}
@@ -1,27 +1,137 @@
 use crate::{
-    leaptypes::{Comment, CommentType, LeapEnum, LeapStruct, LeapType, Name, ValueType},
-    parser::{commentsparser, parser::Parser},
+    leaptypes::{
+        Attribute, Comment, CommentType, LeapConst, LeapDefinition, LeapEnum, LeapSpec, LeapStruct,
+        LeapType, Name, Prop, ValueType,
+    },
+    parser::{commentsparser, cst, itemposition::ItemPosition, parser::Parser, position::Position},
+    textedit::{self, TextEdit},
+    visitor::{walk_value_type, Visitor},
 };
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrailCommentAlignment {
+    // comments of a definition are aligned to the next multiple of indent width after its longest line
+    Definition,
+    // comment is separated from code with a single space
+    Space,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DefinitionOrder {
+    // top level definitions are kept in the source order
+    Source,
+    // top level definitions are sorted by name
+    Alphabetical,
+    // types go before definitions which use them, otherwise source order is kept
+    Topological,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    // width of one indentation level, in tabs mode used to align trailing comments
+    pub indent_width: usize,
+    pub use_tabs: bool,
+    pub trail_comment_alignment: TrailCommentAlignment,
+    // exact number of empty lines between top level definitions, if none empty lines are
+    // preserved (several empty lines are joined into one)
+    pub blank_lines_between_definitions: Option<usize>,
+    // aligned trailing comment which would end after this column is separated with a single
+    // space instead
+    pub max_trail_column: Option<usize>,
+    // trailing comment which would make line longer than this is moved to its own line right
+    // above the item (so it stays in doc comment of the item), code and comments are not wrapped
+    pub max_line_width: Option<usize>,
+    pub definition_order: DefinitionOrder,
+    // sort props of structs and variants of enums by name
+    pub sort_props: bool,
+    // put constants first, then structs, then enums, order inside of group is kept
+    pub group_by_kind: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        FormatOptions {
+            indent_width: 4,
+            use_tabs: false,
+            trail_comment_alignment: TrailCommentAlignment::Definition,
+            blank_lines_between_definitions: None,
+            max_trail_column: None,
+            max_line_width: None,
+            definition_order: DefinitionOrder::Source,
+            sort_props: false,
+            group_by_kind: false,
+        }
+    }
+}
+
+impl FormatOptions {
+    fn indent(&self, level: usize) -> String {
+        if self.use_tabs {
+            "\t".repeat(level)
+        } else {
+            " ".repeat(level * self.indent_width)
+        }
+    }
+
+    fn is_reordering(&self) -> bool {
+        self.definition_order != DefinitionOrder::Source || self.sort_props || self.group_by_kind
+    }
+}
+
 #[derive(Debug)]
 struct Block {
     start: usize,
     next_start: usize,
     trail_indent: usize,
-    new_section: bool,
+    // indentation level
+    indent: usize,
+    // first line of top level definition
+    starts_definition: bool,
     text: String,
 }
 
-pub fn format(data: &str) -> Option<String> {
-    let types = Parser::parse(data).ok()?;
+impl Block {
+    fn new(start: usize, next_start: usize, indent: usize, text: String) -> Self {
+        Block {
+            start,
+            next_start,
+            trail_indent: 0,
+            indent,
+            starts_definition: false,
+            text,
+        }
+    }
+
+    // width of line in columns
+    fn width(&self, options: &FormatOptions) -> usize {
+        self.indent * options.indent_width + self.text.chars().count()
+    }
+}
+
+pub fn format(data: &str) -> Result<String, ItemPosition<String>> {
+    format_with(data, &FormatOptions::default())
+}
+
+pub fn format_with(data: &str, options: &FormatOptions) -> Result<String, ItemPosition<String>> {
+    let formatted = format_source(data, options)?;
+    if options.is_reordering() {
+        reorder(&formatted, options)
+    } else {
+        Ok(formatted)
+    }
+}
+
+fn format_source(data: &str, options: &FormatOptions) -> Result<String, ItemPosition<String>> {
+    let definitions = Parser::parse_definitions(data)?;
     let mut formatted: Vec<Block> = vec![];
-    for next_type in &types {
-        let mut lines = format_type(next_type);
+    for definition in &definitions {
+        let mut lines = format_definition(definition);
         if !lines.is_empty() {
             if let Some(last) = formatted.last_mut() {
-                last.next_start = lines.last().unwrap().start;
+                last.next_start = lines.first().unwrap().start;
             }
-            update_trail_indent(&mut lines);
+            lines[0].starts_definition = true;
+            update_trail_indent(&mut lines, options);
             formatted.append(&mut lines);
         }
     }
@@ -29,47 +139,91 @@ pub fn format(data: &str) -> Option<String> {
         last.next_start = data.len();
     }
     let mut comments = commentsparser::parse(data).into_iter().peekable();
-    let mut result = vec![];
+    // `None` marks start of top level definition
+    let mut result: Vec<Option<String>> = vec![];
     for b in formatted {
-        while comments
-            .peek()
-            .map_or(false, |c| c.position.start < b.start)
-        {
-            let indent = if b.new_section { 0 } else { 4 };
-            result.push(format_comment(&comments.next().unwrap(), indent));
+        if b.starts_definition {
+            result.push(None);
         }
-        let has_trail_comment = comments.peek().map_or(false, |c| match c.comment_type {
+        while comments.peek().is_some_and(|c| c.position.start < b.start) {
+            let indent = options.indent(b.indent);
+            result.push(Some(format_comment(&comments.next().unwrap(), &indent)));
+        }
+        let has_trail_comment = comments.peek().is_some_and(|c| match c.comment_type {
             CommentType::Trail => b.start < c.position.start && c.position.start < b.next_start,
             _ => false,
         });
+        let mut text = options.indent(b.indent);
+        text.push_str(&b.text);
         if has_trail_comment {
-            let indent = b.trail_indent - b.text.len();
-            let mut text = b.text;
-            text.push_str(&format_comment(&comments.next().unwrap(), indent));
-            result.push(text);
-        } else {
-            result.push(b.text);
+            let comment = format_comment(&comments.next().unwrap(), "");
+            let width = b.width(options);
+            // comment starting at column fits into max width
+            let fits = |max: Option<usize>, column: usize| {
+                max.is_none_or(|max| column + comment.chars().count() <= max)
+            };
+            if b.trail_indent > width
+                && fits(options.max_trail_column, b.trail_indent)
+                && fits(options.max_line_width, b.trail_indent)
+            {
+                text.push_str(&" ".repeat(b.trail_indent - width));
+                text.push_str(&comment);
+            } else if fits(options.max_line_width, width + 1) || b.text.starts_with('@') {
+                // comment of attribute line is not a doc comment, it is never moved
+                text.push(' ');
+                text.push_str(&comment);
+            } else {
+                // above attributes of the item
+                let mut i = result.len();
+                while i > 0
+                    && result[i - 1]
+                        .as_ref()
+                        .is_some_and(|l| l.trim_start().starts_with('@'))
+                {
+                    i -= 1;
+                }
+                result.insert(i, Some(options.indent(b.indent) + &comment));
+            }
         }
+        result.push(Some(text));
     }
     for c in comments {
-        result.push(format_comment(&c, 0));
+        result.push(Some(format_comment(&c, "")));
     }
-    // remove repeating separators and separators at the start
+    // remove repeating separators and separators at the start, set separators between definitions
     let mut new_result: Vec<String> = vec![];
+    // skip separators right after forced separators
+    let mut skip_separators = false;
     for r in result {
-        if r.is_empty() {
-            // push only if last is not empty and array is not empty
-            let last_separator_or_empty = new_result.last().map(|s| s.is_empty()).unwrap_or(true);
-            if !last_separator_or_empty {
-                new_result.push(r)
+        match r {
+            None => {
+                if let Some(count) = options.blank_lines_between_definitions {
+                    if !new_result.is_empty() {
+                        while new_result.last().is_some_and(|s| s.is_empty()) {
+                            new_result.pop();
+                        }
+                        new_result.extend((0..count).map(|_| "".to_owned()));
+                        skip_separators = true;
+                    }
+                }
+            }
+            Some(r) if r.is_empty() => {
+                // push only if last is not empty and array is not empty
+                let last_separator_or_empty =
+                    new_result.last().map(|s| s.is_empty()).unwrap_or(true);
+                if !last_separator_or_empty && !skip_separators {
+                    new_result.push(r)
+                }
+            }
+            Some(r) => {
+                skip_separators = false;
+                new_result.push(r);
             }
-        } else {
-            new_result.push(r);
         }
     }
-    result = new_result;
+    let mut result = new_result;
     // remove separators at the end
-    if result.last().map(|s| s.is_empty()).unwrap_or(false) {
+    while result.last().map(|s| s.is_empty()).unwrap_or(false) {
         result.pop();
     }
     let mut result = result.join("\n");
@@ -77,17 +231,393 @@ pub fn format(data: &str) -> Option<String> {
     if !result.is_empty() {
         result.push('\n');
     }
-    Some(result)
+    Ok(result)
+}
+
+// formats top level definitions which can be parsed, blocks with errors are kept as is,
+// returns formatted data and errors, props are sorted in every parsed block, but definitions
+// are ordered and grouped only if there are no errors (otherwise source order is kept)
+pub fn format_tolerant(data: &str, options: &FormatOptions) -> (String, Vec<ItemPosition<String>>) {
+    if let Ok(formatted) = format_with(data, options) {
+        return (formatted, vec![]);
+    }
+    let mut result = String::new();
+    let mut errors = vec![];
+    // source of previous block, and if it was kept as is
+    let mut previous: Option<(String, bool)> = None;
+    for position in cst::split_blocks(data) {
+        let text: String = data
+            .chars()
+            .skip(position.start)
+            .take(position.length)
+            .collect();
+        let (formatted, is_verbatim) = match format_with(&text, options) {
+            Ok(formatted) => (formatted, false),
+            Err(ItemPosition(p, e)) => {
+                errors.push(ItemPosition::new(position.start + p.start, p.length, e));
+                (text.clone(), true)
+            }
+        };
+        if formatted.is_empty() {
+            continue;
+        }
+        if let Some((previous_text, previous_verbatim)) = &previous {
+            if !result.ends_with('\n') {
+                result.push('\n');
+            }
+            let blank_lines = match options.blank_lines_between_definitions {
+                _ if *previous_verbatim => 0,
+                Some(count) => count,
+                None => usize::from(ends_with_empty_line(previous_text)),
+            };
+            result.push_str(&"\n".repeat(blank_lines));
+        }
+        result.push_str(&formatted);
+        previous = Some((text, is_verbatim));
+    }
+    (result, errors)
+}
+
+// formats top level blocks overlapping range, returns edits of changed lines only
+pub fn format_range(
+    data: &str,
+    range: Position,
+    options: &FormatOptions,
+) -> Result<Vec<TextEdit>, ItemPosition<String>> {
+    // empty range selects block under the position
+    let length = data.chars().count();
+    let start = range.start.min(length.saturating_sub(1));
+    let end = range.end().max(start + 1);
+    let blocks: Vec<Position> = cst::split_blocks(data)
+        .into_iter()
+        .filter(|b| b.start < end && start < b.end())
+        .collect();
+    let (Some(first), Some(last)) = (blocks.first(), blocks.last()) else {
+        return Ok(vec![]);
+    };
+    let region = Position::new(first.start, last.end() - first.start);
+    let text: String = data
+        .chars()
+        .skip(region.start)
+        .take(region.length)
+        .collect();
+    // empty lines after the blocks are kept as is
+    let trailing = &text[text.trim_end().len()..];
+    let formatted = format_with(&text, options)
+        .map_err(|ItemPosition(p, e)| ItemPosition::new(region.start + p.start, p.length, e))?;
+    let formatted = if trailing.is_empty() {
+        formatted
+    } else {
+        format!("{}{}", formatted.trim_end_matches('\n'), trailing)
+    };
+    Ok(textedit::diff(&text, &formatted)
+        .into_iter()
+        .map(|mut e| {
+            e.position.start += region.start;
+            e
+        })
+        .collect())
+}
+
+// formats top level block under the position, eg. after typing
+pub fn format_on_type(
+    data: &str,
+    position: usize,
+    options: &FormatOptions,
+) -> Result<Vec<TextEdit>, ItemPosition<String>> {
+    format_range(data, Position::new(position, 0), options)
+}
+
+// renders spec as canonical Leap source, definitions are grouped by path and every group
+// starts with a comment with the path, only doc comments are kept
+pub fn print(spec: &LeapSpec) -> String {
+    print_files(spec)
+        .into_iter()
+        .map(|(path, source)| {
+            if path.is_empty() {
+                source
+            } else {
+                format!("/-- {}\n\n{}", path, source)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// renders spec as canonical Leap source for every path, paths are in order of appearance
+pub fn print_files(spec: &LeapSpec) -> Vec<(String, String)> {
+    let mut files: Vec<(String, Vec<String>)> = vec![];
+    let definitions = spec
+        .iter_type_refs()
+        .map(|t| (t.path(), print_type(t)))
+        .chain(
+            spec.iter_consts()
+                .map(|c| (c.path.as_str(), print_const(c))),
+        );
+    for (path, text) in definitions {
+        match files.iter_mut().find(|(p, _)| p == path) {
+            Some((_, texts)) => texts.push(text),
+            None => files.push((path.to_owned(), vec![text])),
+        }
+    }
+    files
+        .into_iter()
+        .map(|(path, texts)| (path, texts.join("\n")))
+        .collect()
+}
+
+pub fn print_type(leap_type: &LeapType) -> String {
+    let options = FormatOptions::default();
+    let mut lines = vec![];
+    let (keyword, props) = match leap_type {
+        LeapType::Struct(s) => (".struct", &s.props),
+        LeapType::Enum(e) => (".enum", &e.variants),
+    };
+    let header = format!(
+        "{} {}{}",
+        keyword,
+        leap_type.name().get(),
+        format_type_args(leap_type.args())
+    );
+    print_item(
+        &mut lines,
+        leap_type.doc(),
+        leap_type.attributes(),
+        "",
+        header,
+    );
+    for prop in props {
+        let text = match leap_type {
+            LeapType::Struct(_) => format_prop(prop),
+            LeapType::Enum(_) => format_variant(prop),
+        };
+        print_item(
+            &mut lines,
+            prop.doc.as_deref(),
+            &prop.attributes,
+            &options.indent(1),
+            text,
+        );
+    }
+    lines.concat()
+}
+
+pub fn print_const(leap_const: &LeapConst) -> String {
+    let mut lines = vec![];
+    print_item(
+        &mut lines,
+        leap_const.doc.as_deref(),
+        &leap_const.attributes,
+        "",
+        format_const_line(leap_const),
+    );
+    lines.concat()
+}
+
+// doc comment goes first as it is placed before attributes in the source
+fn print_item(
+    lines: &mut Vec<String>,
+    doc: Option<&str>,
+    attributes: &[Attribute],
+    indent: &str,
+    text: String,
+) {
+    for line in doc.into_iter().flat_map(|d| d.split('\n')) {
+        lines.push(format!("{}/-- {}\n", indent, line));
+    }
+    for attribute in attributes {
+        lines.push(format!("{}{}\n", indent, attribute));
+    }
+    lines.push(format!("{}{}\n", indent, text));
+}
+
+fn ends_with_empty_line(text: &str) -> bool {
+    text[text.trim_end().len()..].matches('\n').count() >= 2
+}
+
+// reorders blocks of formatted data, each definition is moved together with its attributes
+// and comments directly above it
+fn reorder(formatted: &str, options: &FormatOptions) -> Result<String, ItemPosition<String>> {
+    let definitions = Parser::parse_definitions(formatted)?;
+    let mut preamble = String::new();
+    let mut blocks: Vec<(&LeapDefinition, String)> = vec![];
+    for position in cst::split_blocks(formatted) {
+        let text: String = formatted
+            .chars()
+            .skip(position.start)
+            .take(position.length)
+            .collect();
+        let definition = definitions
+            .iter()
+            .find(|d| position.start <= d.position().start && d.position().start < position.end());
+        match definition {
+            Some(d) if options.sort_props => blocks.push((d, sort_props(&text, d))),
+            Some(d) => blocks.push((d, text)),
+            None => preamble.push_str(&text),
+        }
+    }
+    let has_separators = blocks.iter().any(|(_, text)| ends_with_empty_line(text));
+    let mut order: Vec<usize> = match options.definition_order {
+        DefinitionOrder::Source => (0..blocks.len()).collect(),
+        DefinitionOrder::Alphabetical => {
+            let mut order: Vec<usize> = (0..blocks.len()).collect();
+            order.sort_by(|a, b| blocks[*a].0.name().cmp(blocks[*b].0.name()));
+            order
+        }
+        DefinitionOrder::Topological => {
+            topological_order(&blocks.iter().map(|(d, _)| *d).collect::<Vec<_>>())
+        }
+    };
+    if options.group_by_kind {
+        order.sort_by_key(|i| match blocks[*i].0 {
+            LeapDefinition::Const(_) => 0,
+            LeapDefinition::Type(LeapType::Struct(_)) => 1,
+            LeapDefinition::Type(LeapType::Enum(_)) => 2,
+        });
+    }
+    let mut result = preamble;
+    for (n, i) in order.iter().enumerate() {
+        if n > 0 && has_separators {
+            result.push('\n');
+        }
+        result.push_str(blocks[*i].1.trim_end());
+        result.push('\n');
+    }
+    format_source(&result, options)
 }
 
-fn update_trail_indent(lines: &mut [Block]) {
-    let max_len = lines.iter().map(|b| b.text.len()).max().unwrap_or(0);
-    let indent = (max_len / 4) * 4 + 4;
+// sorts props or variants in formatted block of definition, comments and attributes above
+// a prop are moved with it
+fn sort_props(text: &str, definition: &LeapDefinition) -> String {
+    let props = match definition {
+        LeapDefinition::Type(LeapType::Struct(s)) => &s.props,
+        LeapDefinition::Type(LeapType::Enum(e)) => &e.variants,
+        LeapDefinition::Const(_) => return text.to_owned(),
+    };
+    let lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let Some(header) = lines
+        .iter()
+        .position(|l| l.starts_with(".struct") || l.starts_with(".enum"))
+    else {
+        return text.to_owned();
+    };
+    // body is indented, comments after it are not
+    let body_end = lines
+        .iter()
+        .rposition(|l| l.starts_with([' ', '\t']))
+        .map_or(header + 1, |i| (i + 1).max(header + 1));
+    let mut items = vec![];
+    let mut pending = String::new();
+    for line in &lines[header + 1..body_end] {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        pending.push_str(line);
+        if !trimmed.starts_with("/--") && !trimmed.starts_with('@') {
+            items.push(std::mem::take(&mut pending));
+        }
+    }
+    if items.len() != props.len() {
+        return text.to_owned();
+    }
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|a, b| props[*a].name.cmp(&props[*b].name));
+    let mut result = lines[..=header].concat();
+    for i in order {
+        result.push_str(&items[i]);
+    }
+    // comments after the last prop stay at the end
+    result.push_str(&pending);
+    result.push_str(&lines[body_end..].concat());
+    result
+}
+
+// dependencies go first, on cycles source order is used
+fn topological_order(definitions: &[&LeapDefinition]) -> Vec<usize> {
+    fn visit(
+        i: usize,
+        definitions: &[&LeapDefinition],
+        visited: &mut [bool],
+        order: &mut Vec<usize>,
+    ) {
+        if visited[i] {
+            return;
+        }
+        visited[i] = true;
+        let mut names = TypeNames(vec![]);
+        names.visit_definition(definitions[i]);
+        for name in names.0 {
+            let dependency = definitions
+                .iter()
+                .position(|d| d.as_type().is_some_and(|t| t.name().get() == name));
+            if let Some(j) = dependency {
+                visit(j, definitions, visited, order);
+            }
+        }
+        order.push(i);
+    }
+    let mut visited = vec![false; definitions.len()];
+    let mut order = vec![];
+    for i in 0..definitions.len() {
+        visit(i, definitions, &mut visited, &mut order);
+    }
+    order
+}
+
+// names of referenced types
+struct TypeNames<'a>(Vec<&'a str>);
+
+impl<'a> Visitor<'a> for TypeNames<'a> {
+    fn visit_value_type(&mut self, value_type: &'a ValueType) {
+        if let ValueType::LeapType { name, .. } = value_type {
+            self.0.push(name.get());
+        }
+        walk_value_type(self, value_type);
+    }
+}
+
+fn update_trail_indent(lines: &mut [Block], options: &FormatOptions) {
+    let indent = match options.trail_comment_alignment {
+        TrailCommentAlignment::Definition => {
+            let width = options.indent_width.max(1);
+            let max_len = lines.iter().map(|b| b.width(options)).max().unwrap_or(0);
+            (max_len / width) * width + width
+        }
+        TrailCommentAlignment::Space => 0,
+    };
     for b in lines {
         b.trail_indent = indent;
     }
 }
 
+fn format_definition(definition: &LeapDefinition) -> Vec<Block> {
+    match definition {
+        LeapDefinition::Type(t) => format_type(t),
+        LeapDefinition::Const(c) => format_const(c),
+    }
+}
+
+fn format_const(leap_const: &LeapConst) -> Vec<Block> {
+    let mut lines = format_attributes(&leap_const.attributes, leap_const.position.start, 0);
+    lines.push(Block::new(
+        leap_const.position.start,
+        leap_const.position.end(),
+        0,
+        format_const_line(leap_const),
+    ));
+    lines
+}
+
+fn format_const_line(leap_const: &LeapConst) -> String {
+    format!(
+        ".const {}: {} = {}",
+        leap_const.name.get(),
+        format_prop_type(&leap_const.value_type),
+        leap_const.value
+    )
+}
+
 fn format_type(leap_type: &LeapType) -> Vec<Block> {
     let mut lines = vec![];
     let mut type_lines = match leap_type {
@@ -99,91 +629,108 @@ fn format_type(leap_type: &LeapType) -> Vec<Block> {
 }
 
 fn format_struct(leap_struct: &LeapStruct) -> Vec<Block> {
-    let mut lines = vec![];
+    let mut lines = format_attributes(&leap_struct.attributes, leap_struct.position.start, 0);
     let text = format!(
         ".struct {}{}",
         leap_struct.name.get(),
         format_type_args(&leap_struct.args)
     );
-    let next_start = if let Some(last) = leap_struct.props.last() {
-        last.position.start
+    let next_start = if let Some(first) = leap_struct.props.first() {
+        prop_start(first)
     } else {
         leap_struct.position.end()
     };
-    lines.push(Block {
-        start: leap_struct.position.start,
-        next_start,
-        trail_indent: 0,
-        new_section: true,
-        text,
-    });
+    lines.push(Block::new(leap_struct.position.start, next_start, 0, text));
     for i in 0..leap_struct.props.len() {
         let prop = &leap_struct.props[i];
         let next_prop = leap_struct.props.get(i + 1);
-        let text = format!(
-            "    {}: {}",
-            prop.name.get(),
-            format_prop_type(&prop.prop_type)
-        );
+        lines.append(&mut format_attributes(
+            &prop.attributes,
+            prop.position.start,
+            1,
+        ));
+        let text = format_prop(prop);
         let next_start = if let Some(next) = next_prop {
-            next.position.start
+            prop_start(next)
         } else {
             prop.position.end()
         };
-        lines.push(Block {
-            start: prop.position.start,
-            next_start,
-            trail_indent: 0,
-            new_section: false,
-            text,
-        })
+        lines.push(Block::new(prop.position.start, next_start, 1, text))
     }
     lines
 }
 
 fn format_enum(leap_enum: &LeapEnum) -> Vec<Block> {
-    let mut lines = vec![];
+    let mut lines = format_attributes(&leap_enum.attributes, leap_enum.position.start, 0);
     let text = format!(
         ".enum {}{}",
         leap_enum.name.get(),
         format_type_args(&leap_enum.args)
     );
-    let next_start = if let Some(last) = leap_enum.variants.last() {
-        last.position.start
+    let next_start = if let Some(first) = leap_enum.variants.first() {
+        prop_start(first)
     } else {
         leap_enum.position.end()
     };
-    lines.push(Block {
-        start: leap_enum.position.start,
-        next_start,
-        trail_indent: 0,
-        new_section: true,
-        text,
-    });
+    lines.push(Block::new(leap_enum.position.start, next_start, 0, text));
     for i in 0..leap_enum.variants.len() {
         let variant = &leap_enum.variants[i];
         let next_var = leap_enum.variants.get(i + 1);
-        let text = if variant.name.get() == variant.prop_type.name() {
-            format!("    {}", format_prop_type(&variant.prop_type))
-        } else {
-            format!(
-                "    {}: {}",
-                variant.name.get(),
-                format_prop_type(&variant.prop_type)
-            )
-        };
+        lines.append(&mut format_attributes(
+            &variant.attributes,
+            variant.position.start,
+            1,
+        ));
+        let text = format_variant(variant);
         let next_start = if let Some(next) = next_var {
-            next.position.start
+            prop_start(next)
         } else {
             variant.position.end()
         };
-        lines.push(Block {
-            start: variant.position.start,
+        lines.push(Block::new(variant.position.start, next_start, 1, text))
+    }
+    lines
+}
+
+fn format_prop(prop: &Prop) -> String {
+    let mut text = format!("{}: {}", prop.name.get(), format_prop_type(&prop.prop_type));
+    if let Some(default) = &prop.default {
+        text.push_str(&format!(" = {}", default));
+    }
+    text
+}
+
+fn format_variant(variant: &Prop) -> String {
+    if variant.name.get() == variant.prop_type.name() {
+        format_prop_type(&variant.prop_type)
+    } else {
+        format!(
+            "{}: {}",
+            variant.name.get(),
+            format_prop_type(&variant.prop_type)
+        )
+    }
+}
+
+// start of the first line of property, including attributes
+fn prop_start(prop: &Prop) -> usize {
+    prop.attributes
+        .first()
+        .map_or(prop.position.start, |a| a.position.start)
+}
+
+fn format_attributes(attributes: &[Attribute], item_start: usize, indent: usize) -> Vec<Block> {
+    let mut lines = vec![];
+    for (i, attribute) in attributes.iter().enumerate() {
+        let next_start = attributes
+            .get(i + 1)
+            .map_or(item_start, |a| a.position.start);
+        lines.push(Block::new(
+            attribute.position.start,
             next_start,
-            trail_indent: 0,
-            new_section: false,
-            text,
-        })
+            indent,
+            attribute.to_string(),
+        ));
     }
     lines
 }
@@ -203,10 +750,10 @@ fn format_type_args(args: &[Name]) -> String {
 
 fn format_prop_type(prop_type: &ValueType) -> String {
     match prop_type {
-        ValueType::Simple(t) => t.name(),
-        ValueType::List(t) => format!("list[{}]", format_prop_type(t)),
+        ValueType::Simple(t, _) => t.name(),
+        ValueType::List(t, _) => format!("list[{}]", format_prop_type(t)),
         ValueType::TypeArg(n) => n.get().to_owned(),
-        ValueType::LeapType { name, args } => {
+        ValueType::LeapType { name, args, .. } => {
             if args.is_empty() {
                 name.get().to_owned()
             } else {
@@ -221,8 +768,7 @@ fn format_prop_type(prop_type: &ValueType) -> String {
     }
 }
 
-fn format_comment(comment: &Comment, indent: usize) -> String {
-    let indent = String::from_utf8(vec![b' '; indent]).unwrap();
+fn format_comment(comment: &Comment, indent: &str) -> String {
     match comment.comment_type {
         CommentType::Line | CommentType::Trail => format!("{}/-- {}", indent, comment.comment),
         CommentType::Separator => "".to_owned(),
@@ -235,23 +781,12 @@ mod tests {
 
     #[test]
     fn test_update_trail_indent() {
-        let mut blocks = vec![Block {
-            start: 0,
-            next_start: 0,
-            trail_indent: 0,
-            new_section: false,
-            text: "aaaa".to_owned(),
-        }];
-        update_trail_indent(&mut blocks);
+        let options = FormatOptions::default();
+        let mut blocks = vec![Block::new(0, 0, 0, "aaaa".to_owned())];
+        update_trail_indent(&mut blocks, &options);
         assert_eq!(blocks[0].trail_indent, 8);
-        let mut blocks = vec![Block {
-            start: 0,
-            next_start: 0,
-            trail_indent: 0,
-            new_section: false,
-            text: "aaaaaaa".to_owned(),
-        }];
-        update_trail_indent(&mut blocks);
+        let mut blocks = vec![Block::new(0, 0, 0, "aaaaaaa".to_owned())];
+        update_trail_indent(&mut blocks, &options);
         assert_eq!(blocks[0].trail_indent, 8);
     }
 
@@ -266,6 +801,10 @@ mod tests {
             format(".struct    s1  a:   int").unwrap(),
             ".struct s1\n    a: int\n"
         );
+        assert_eq!(
+            format(".struct    s1  a:   u8 b: list[ uuid ]").unwrap(),
+            ".struct s1\n    a: u8\n    b: list[uuid]\n"
+        );
         assert_eq!(format(".enum    e1    s1").unwrap(), ".enum e1\n    s1\n");
         assert_eq!(
             format(".enum    e1    aaa:    s1").unwrap(),
@@ -277,6 +816,351 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_with_options() {
+        let data = "/ header\n\n\n.struct s1 / text\na: int\nlong-name: str / longer text\n.enum e1\n\n\n/ doc\n.struct s2";
+        assert_eq!(
+            format_with(
+                data,
+                &FormatOptions {
+                    indent_width: 2,
+                    trail_comment_alignment: TrailCommentAlignment::Space,
+                    blank_lines_between_definitions: Some(2),
+                    ..Default::default()
+                }
+            )
+            .unwrap(),
+            "/-- header\n\n.struct s1 /-- text\n  a: int\n  long-name: str /-- longer text\n\n\n.enum e1\n\n\n/-- doc\n.struct s2\n"
+        );
+        assert_eq!(
+            format_with(
+                data,
+                &FormatOptions {
+                    use_tabs: true,
+                    blank_lines_between_definitions: Some(0),
+                    max_trail_column: Some(28),
+                    ..Default::default()
+                }
+            )
+            .unwrap(),
+            "/-- header\n\n.struct s1          /-- text\n\ta: int\n\tlong-name: str /-- longer text\n.enum e1\n/-- doc\n.struct s2\n"
+        );
+    }
+
+    #[test]
+    fn test_format_max_line_width() {
+        let data = ".struct s1 / text\n/ doc\n@min 0\nlong-name: int / longer text\nb: str / short";
+        let options = FormatOptions {
+            max_line_width: Some(30),
+            ..Default::default()
+        };
+        let formatted = format_with(data, &options).unwrap();
+        assert_eq!(
+            formatted,
+            ".struct s1          /-- text\n    /-- doc\n    /-- longer text\n    @min 0\n    long-name: int\n    b: str          /-- short\n"
+        );
+        // moved comment stays in doc comment of the prop
+        let doc = |data: &str| {
+            Parser::parse(data).unwrap()[0].as_struct().unwrap().props[0]
+                .doc
+                .clone()
+        };
+        assert_eq!(doc(data).as_deref(), Some("doc\nlonger text"));
+        assert_eq!(doc(&formatted), doc(data));
+        assert_eq!(format_with(&formatted, &options).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_format_errors() {
+        assert_eq!(
+            format(".struct s1\n    a:").unwrap_err(),
+            ItemPosition::new(17, 0, "Expecting name".to_owned())
+        );
+        let (formatted, errors) = format_tolerant(
+            "/ header\n\n.struct   s1\n\n.struct s2 a:\n\n\n.enum   e1 s1\n.struct s3 a:int",
+            &FormatOptions::default(),
+        );
+        assert_eq!(
+            formatted,
+            "/-- header\n\n.struct s1\n\n.struct s2 a:\n\n\n.enum e1\n    s1\n.struct s3\n    a: int\n"
+        );
+        assert_eq!(
+            errors,
+            vec![ItemPosition::new(37, 0, "Expecting name".to_owned())]
+        );
+        let options = FormatOptions {
+            definition_order: DefinitionOrder::Alphabetical,
+            sort_props: true,
+            ..Default::default()
+        };
+        let (formatted, errors) = format_tolerant(
+            ".struct s2 b: int a: int
+.struct s1",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            ".struct s1
+.struct s2
+    a: int
+    b: int
+"
+        );
+        assert!(errors.is_empty());
+        // with errors order of definitions is kept
+        let (formatted, errors) = format_tolerant(
+            ".struct s2 b: int a: int
+.struct s3 a:
+.struct s1",
+            &options,
+        );
+        assert_eq!(
+            formatted,
+            ".struct s2
+    a: int
+    b: int
+.struct s3 a:
+.struct s1
+"
+        );
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn test_format_range() {
+        let data = ".struct   s1\n\n.struct s2 / text\n  a:int\n    b: str\n\n\n.enum   e1 s1\n";
+        let options = FormatOptions::default();
+        let edits = format_range(data, Position::new(20, 3), &options).unwrap();
+        assert_eq!(
+            edits,
+            vec![TextEdit::new(
+                Position::new(14, 26),
+                ".struct s2  /-- text\n    a: int\n".to_owned()
+            )]
+        );
+        assert_eq!(
+            textedit::apply_edits(data, &edits),
+            ".struct   s1\n\n.struct s2  /-- text\n    a: int\n    b: str\n\n\n.enum   e1 s1\n"
+        );
+        let edits = format_on_type(data, 60, &options).unwrap();
+        assert_eq!(
+            textedit::apply_edits(data, &edits),
+            ".struct   s1\n\n.struct s2 / text\n  a:int\n    b: str\n\n\n.enum e1\n    s1\n"
+        );
+        assert_eq!(
+            format_range(".struct s1 a:", Position::new(0, 1), &options).unwrap_err(),
+            ItemPosition::new(13, 0, "Expecting name".to_owned())
+        );
+        assert!(format_on_type("", 0, &options).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_print() {
+        let mut definitions = Parser::parse_definitions(
+            "/ user\n@since 1\n.struct user[t]   id: t / key\n  roles: list[role] = []",
+        )
+        .unwrap();
+        let mut other =
+            Parser::parse_definitions(".enum role admin  other: str\n.const max: int = 1").unwrap();
+        definitions
+            .iter_mut()
+            .for_each(|d| d.set_path("a.leap".to_owned()));
+        other
+            .iter_mut()
+            .for_each(|d| d.set_path("b.leap".to_owned()));
+        definitions.append(&mut other);
+        let spec = LeapSpec::from_definitions(definitions);
+        assert_eq!(
+            print(&spec),
+            "/-- a.leap\n\n/-- user\n@since 1\n.struct user[t]\n    /-- key\n    id: t\n    roles: list[role] = []\n\n/-- b.leap\n\n.enum role\n    admin\n    other: str\n\n.const max: int = 1\n"
+        );
+    }
+
+    // simple pseudo random generator to avoid dependencies
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, n: usize) -> usize {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((self.0 >> 33) as usize) % n
+        }
+    }
+
+    fn random_type(r: &mut Random, args: &[&str], arg_counts: &[usize], depth: usize) -> String {
+        const SIMPLE: [&str; 6] = ["str", "int", "float", "bool", "u8", "uuid"];
+        match r.next(if depth > 2 { 2 } else { 5 }) {
+            0 => SIMPLE[r.next(SIMPLE.len())].to_owned(),
+            1 if !args.is_empty() => args[r.next(args.len())].to_owned(),
+            1 | 2 => format!("list[ {}]", random_type(r, args, arg_counts, depth + 1)),
+            _ => {
+                let k = r.next(arg_counts.len());
+                let applied: Vec<String> = (0..arg_counts[k])
+                    .map(|_| random_type(r, args, arg_counts, depth + 1))
+                    .collect();
+                if applied.is_empty() {
+                    format!("t{}", k)
+                } else {
+                    format!("t{}[{}]", k, applied.join("  "))
+                }
+            }
+        }
+    }
+
+    fn random_source(r: &mut Random) -> String {
+        let arg_counts: Vec<usize> = (0..1 + r.next(5)).map(|_| r.next(3)).collect();
+        let mut source = String::new();
+        for (i, count) in arg_counts.iter().enumerate() {
+            let args = &["a", "b"][..*count];
+            match r.next(4) {
+                0 => source.push_str(&format!("/ doc {}\n", i)),
+                1 => source.push_str(&format!("/ doc\n/-- more \"{}\"\n", i)),
+                _ => {}
+            }
+            if r.next(3) == 0 {
+                source.push_str(&format!("@since {}\n", i));
+            }
+            let is_struct = r.next(2) == 0;
+            let keyword = if is_struct { ".struct" } else { ".enum" };
+            source.push_str(&format!("{}  t{}", keyword, i));
+            if !args.is_empty() {
+                source.push_str(&format!("[{}]", args.join(" ")));
+            }
+            source.push('\n');
+            for j in 0..r.next(4) {
+                if r.next(3) == 0 {
+                    source.push_str("  / prop doc\n");
+                }
+                if r.next(4) == 0 {
+                    source.push_str("  @deprecated\n");
+                }
+                let prop_type = random_type(r, args, &arg_counts, 0);
+                let default = match prop_type.as_str() {
+                    "int" if is_struct => format!(" = {}", j),
+                    "str" if is_struct => format!(" = \"s/{}\"", j),
+                    _ => "".to_owned(),
+                };
+                source.push_str(&format!("  p{}: {}{}", j, prop_type, default));
+                if r.next(3) == 0 {
+                    source.push_str(" / trail");
+                }
+                source.push('\n');
+            }
+            if r.next(2) == 0 {
+                source.push('\n');
+            }
+        }
+        if r.next(2) == 0 {
+            source.push_str("/ const\n.const c: int = -5\n");
+        }
+        source
+    }
+
+    fn assert_same_props(a: &[Prop], b: &[Prop]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.prop_type, b.prop_type);
+            assert_eq!(a.default, b.default);
+            assert_eq!(a.doc, b.doc);
+            assert_same_attributes(&a.attributes, &b.attributes);
+        }
+    }
+
+    fn assert_same_attributes(a: &[Attribute], b: &[Attribute]) {
+        let names = |attributes: &[Attribute]| {
+            attributes
+                .iter()
+                .map(|a| (a.name.clone(), a.value.clone()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(a), names(b));
+    }
+
+    fn assert_same_spec(a: &LeapSpec, b: &LeapSpec) {
+        let a_types: Vec<_> = a.iter_type_refs().collect();
+        let b_types: Vec<_> = b.iter_type_refs().collect();
+        assert_eq!(a_types.len(), b_types.len());
+        for (a, b) in a_types.into_iter().zip(b_types) {
+            assert_eq!(a.name(), b.name());
+            assert_eq!(a.args(), b.args());
+            assert_eq!(a.doc(), b.doc());
+            assert_eq!(a.path(), b.path());
+            assert_eq!(a.is_struct(), b.is_struct());
+            assert_same_attributes(a.attributes(), b.attributes());
+            match (a, b) {
+                (LeapType::Struct(a), LeapType::Struct(b)) => assert_same_props(&a.props, &b.props),
+                (LeapType::Enum(a), LeapType::Enum(b)) => {
+                    assert_same_props(&a.variants, &b.variants)
+                }
+                _ => unreachable!(),
+            }
+        }
+        let a_consts: Vec<_> = a.iter_consts().collect();
+        let b_consts: Vec<_> = b.iter_consts().collect();
+        assert_eq!(a_consts.len(), b_consts.len());
+        for (a, b) in a_consts.into_iter().zip(b_consts) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.value_type, b.value_type);
+            assert_eq!(a.value, b.value);
+            assert_eq!(a.doc, b.doc);
+            assert_eq!(a.path, b.path);
+        }
+    }
+
+    #[test]
+    fn test_print_round_trip() {
+        let mut r = Random(7);
+        for _ in 0..200 {
+            let sources = [random_source(&mut r), random_source(&mut r)];
+            let mut definitions = vec![];
+            for (i, source) in sources.iter().enumerate() {
+                let mut parsed = Parser::parse_definitions(source).unwrap();
+                parsed
+                    .iter_mut()
+                    .for_each(|d| d.set_path(format!("{}.leap", i)));
+                definitions.append(&mut parsed);
+            }
+            let spec = LeapSpec::from_definitions(definitions);
+            let mut printed_definitions = vec![];
+            for (path, source) in print_files(&spec) {
+                // printed source is already formatted
+                assert_eq!(format(&source).unwrap(), source);
+                let mut parsed = Parser::parse_definitions(&source).unwrap();
+                parsed.iter_mut().for_each(|d| d.set_path(path.clone()));
+                printed_definitions.append(&mut parsed);
+            }
+            assert_same_spec(&spec, &LeapSpec::from_definitions(printed_definitions));
+            let printed = Parser::parse(&print(&spec)).unwrap();
+            assert_eq!(printed.len(), spec.iter_type_refs().count());
+        }
+    }
+
+    #[test]
+    fn test_format_ordering() {
+        let data = "/ header\n\n/ user\n.struct user\n    role: role\n    /-- name\n    name: str\n    id: int /-- key\n    / last\n\n.enum role\n    admin\n    guest\n\n@doc \"x\"\n.struct admin\n    level: int\n.struct guest\n.const max-level: int = 10\n";
+        let options = FormatOptions {
+            definition_order: DefinitionOrder::Alphabetical,
+            sort_props: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_with(data, &options).unwrap(),
+            "/-- header\n\n@doc \"x\"\n.struct admin\n    level: int\n\n.struct guest\n\n.const max-level: int = 10\n\n.enum role\n    admin\n    guest\n\n/-- user\n.struct user\n    id: int     /-- key\n    /-- name\n    name: str\n    role: role\n/-- last\n"
+        );
+        let options = FormatOptions {
+            definition_order: DefinitionOrder::Topological,
+            group_by_kind: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_with(data, &options).unwrap(),
+            "/-- header\n\n.const max-level: int = 10\n\n@doc \"x\"\n.struct admin\n    level: int\n\n.struct guest\n\n/-- user\n.struct user\n    role: role\n    /-- name\n    name: str\n    id: int     /-- key\n/-- last\n\n.enum role\n    admin\n    guest\n"
+        );
+    }
+
     #[test]
     fn test_format_with_comments() {
         assert_eq!(format("").unwrap(), "");
@@ -353,6 +1237,54 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_format_attributes() {
+        assert_eq!(
+            format("@deprecated  \n .struct s1\n@since   2.3  \n a: int").unwrap(),
+            "@deprecated\n.struct s1\n    @since 2.3\n    a: int\n"
+        );
+        assert_eq!(
+            format("/ text\n@json-name   userId / trail\n.enum e1\n@deprecated\nv1: s1").unwrap(),
+            "/-- text\n@json-name userId   /-- trail\n.enum e1\n    @deprecated\n    v1: s1\n"
+        );
+        assert_eq!(
+            format(".struct s1\n    a: int\n    @min 0\n    b: int / trail").unwrap(),
+            ".struct s1\n    a: int\n    @min 0\n    b: int  /-- trail\n"
+        );
+        assert_eq!(
+            format(".struct s1\n@format http://x/y / trail\na: str").unwrap(),
+            ".struct s1\n    @format http://x/y  /-- trail\n    a: str\n"
+        );
+    }
+
+    #[test]
+    fn test_format_trail_comments() {
+        assert_eq!(
+            format(".struct s1\na: int / text\nb: int").unwrap(),
+            ".struct s1\n    a: int  /-- text\n    b: int\n"
+        );
+        assert_eq!(
+            format(".struct s1\na: int\n.struct s2 / text\nb: int").unwrap(),
+            ".struct s1\n    a: int\n.struct s2  /-- text\n    b: int\n"
+        );
+    }
+
+    #[test]
+    fn test_format_consts() {
+        assert_eq!(
+            format(".const   max-page-size  :int=100 / text\n\n\n.struct s1\n@since 2\n.const version:   str = \"1.2\"").unwrap(),
+            ".const max-page-size: int = 100 /-- text\n\n.struct s1\n@since 2\n.const version: str = \"1.2\"\n"
+        );
+    }
+
+    #[test]
+    fn test_format_defaults() {
+        assert_eq!(
+            format(".struct s1 a: int=3 b:float   =  -1.0\nc: str = \"a/\\\"b\" / text\nd: bool = true e: list[int] = [ ] f: log-mode = log-mode.info").unwrap(),
+            ".struct s1\n    a: int = 3\n    b: float = -1.0\n    c: str = \"a/\\\"b\"            /-- text\n    d: bool = true\n    e: list[int] = []\n    f: log-mode = log-mode.info\n"
+        );
+    }
+
     #[test]
     fn test_format_complex() {
         let formatted = format(
@@ -28,7 +28,7 @@ impl<T> Eq for Handle<T> {}
 
 impl<T> Clone for Handle<T> {
     fn clone(&self) -> Self {
-        Self::new(self.0)
+        *self
     }
 }
 
@@ -7,14 +7,19 @@
 // todo: checks - type args should be unique relative to struct and enum names, same type arg names can be used in different types
 use crate::handle::Handle;
 use crate::naming;
+use crate::parser::patherror::PathError;
 use crate::parser::position::Position;
 use crate::prop_recursion_check::PropRecursionCheck;
+use crate::rename;
+use crate::visitor::{walk_enum_mut, walk_value_type_mut, VisitorMut};
 use std::collections::HashMap;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::mem;
 
 // todo: trait Name to String
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Name {
     name: String,
     alias: Option<String>,
@@ -22,63 +27,175 @@ pub struct Name {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SimpleType {
+    #[cfg_attr(feature = "serde", serde(rename = "str"))]
     String,
+    #[cfg_attr(feature = "serde", serde(rename = "int"))]
     Integer,
+    #[cfg_attr(feature = "serde", serde(rename = "float"))]
     Float,
+    #[cfg_attr(feature = "serde", serde(rename = "bool"))]
     Boolean,
+    #[cfg_attr(feature = "serde", serde(rename = "i8"))]
+    Int8,
+    #[cfg_attr(feature = "serde", serde(rename = "i16"))]
+    Int16,
+    #[cfg_attr(feature = "serde", serde(rename = "i32"))]
+    Int32,
+    #[cfg_attr(feature = "serde", serde(rename = "i64"))]
+    Int64,
+    #[cfg_attr(feature = "serde", serde(rename = "u8"))]
+    UInt8,
+    #[cfg_attr(feature = "serde", serde(rename = "u16"))]
+    UInt16,
+    #[cfg_attr(feature = "serde", serde(rename = "u32"))]
+    UInt32,
+    #[cfg_attr(feature = "serde", serde(rename = "u64"))]
+    UInt64,
+    #[cfg_attr(feature = "serde", serde(rename = "bytes"))]
+    Bytes,
+    #[cfg_attr(feature = "serde", serde(rename = "decimal"))]
+    Decimal,
+    #[cfg_attr(feature = "serde", serde(rename = "date"))]
+    Date,
+    #[cfg_attr(feature = "serde", serde(rename = "datetime"))]
+    DateTime,
+    #[cfg_attr(feature = "serde", serde(rename = "duration"))]
+    Duration,
+    #[cfg_attr(feature = "serde", serde(rename = "uuid"))]
+    Uuid,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+// every node keeps its position, positions are ignored in comparison and hashing (same as in `Name`)
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum ValueType {
-    Simple(SimpleType),
-    List(Box<ValueType>),
+    Simple(SimpleType, Position),
+    List(Box<ValueType>, Position),
     TypeArg(Name),
-    LeapType { name: Name, args: Vec<ValueType> },
+    LeapType {
+        name: Name,
+        args: Vec<ValueType>,
+        position: Position,
+    },
+}
+
+// literal value, used for defaults
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Literal {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+    // `[]`
+    EmptyList,
+    // `enum-name.variant-name`
+    EnumVariant { enum_name: Name, variant: Name },
+}
+
+// annotation in form of `@name value`, value is optional and is the rest of the line
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Attribute {
+    pub name: String,
+    pub value: Option<String>,
+    pub position: Position,
 }
 
 // todo: rename -> Property
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Prop {
     pub name: Name,
     pub prop_type: ValueType,
+    pub default: Option<Literal>,
+    pub attributes: Vec<Attribute>,
+    pub doc: Option<String>,
     pub position: Position,
     pub is_recursive: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LeapStruct {
     pub name: Name,
     pub args: Vec<Name>,
     pub props: Vec<Prop>,
+    pub attributes: Vec<Attribute>,
+    pub doc: Option<String>,
     pub path: String,
     pub position: Position,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LeapEnum {
     pub name: Name,
     pub args: Vec<Name>,
     pub variants: Vec<Prop>,
+    pub attributes: Vec<Attribute>,
+    pub doc: Option<String>,
     pub path: String,
     pub position: Position,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum LeapType {
     Struct(LeapStruct),
     Enum(LeapEnum),
 }
 
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeapConst {
+    pub name: Name,
+    pub value_type: ValueType,
+    pub value: Literal,
+    pub attributes: Vec<Attribute>,
+    pub doc: Option<String>,
+    pub path: String,
+    pub position: Position,
+}
+
+// top level definition of the spec
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LeapDefinition {
+    Type(LeapType),
+    Const(LeapConst),
+}
+
 pub type LeapTypeHandle = Handle<LeapType>;
 
 #[derive(Debug)]
 pub struct LeapSpec {
     types: Vec<LeapType>,
     name_to_type: HashMap<String, LeapTypeHandle>,
+    consts: Vec<LeapConst>,
+    name_to_const: HashMap<String, usize>,
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Comment {
     pub comment: String,
     pub comment_type: CommentType,
@@ -86,6 +203,11 @@ pub struct Comment {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum CommentType {
     // comment takes full line
     Line,
@@ -180,14 +302,277 @@ impl Hash for Name {
     }
 }
 
+// names of builtin types (`list` and simple types), types and constants can't use them
+pub fn is_builtin_name(name: &str) -> bool {
+    name == "list" || SimpleType::from_name(name).is_some()
+}
+
 impl SimpleType {
+    pub const ALL: [SimpleType; 18] = [
+        SimpleType::String,
+        SimpleType::Integer,
+        SimpleType::Float,
+        SimpleType::Boolean,
+        SimpleType::Int8,
+        SimpleType::Int16,
+        SimpleType::Int32,
+        SimpleType::Int64,
+        SimpleType::UInt8,
+        SimpleType::UInt16,
+        SimpleType::UInt32,
+        SimpleType::UInt64,
+        SimpleType::Bytes,
+        SimpleType::Decimal,
+        SimpleType::Date,
+        SimpleType::DateTime,
+        SimpleType::Duration,
+        SimpleType::Uuid,
+    ];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SimpleType::String => "str",
+            SimpleType::Integer => "int",
+            SimpleType::Float => "float",
+            SimpleType::Boolean => "bool",
+            SimpleType::Int8 => "i8",
+            SimpleType::Int16 => "i16",
+            SimpleType::Int32 => "i32",
+            SimpleType::Int64 => "i64",
+            SimpleType::UInt8 => "u8",
+            SimpleType::UInt16 => "u16",
+            SimpleType::UInt32 => "u32",
+            SimpleType::UInt64 => "u64",
+            SimpleType::Bytes => "bytes",
+            SimpleType::Decimal => "decimal",
+            SimpleType::Date => "date",
+            SimpleType::DateTime => "datetime",
+            SimpleType::Duration => "duration",
+            SimpleType::Uuid => "uuid",
+        }
+    }
+
     pub fn name(&self) -> String {
+        self.as_str().to_owned()
+    }
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            // todo: replace strings with constants
-            SimpleType::String => "str".to_owned(),
-            SimpleType::Integer => "int".to_owned(),
-            SimpleType::Float => "float".to_owned(),
-            SimpleType::Boolean => "bool".to_owned(),
+            Self::Integer(v) => write!(f, "{}", v),
+            // debug formatting always keeps fractional part or exponent, eg. `1.0`
+            Self::Float(v) => write!(f, "{:?}", v),
+            Self::String(v) => {
+                write!(f, "\"")?;
+                for c in v.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        _ => write!(f, "{}", c)?,
+                    }
+                }
+                write!(f, "\"")
+            }
+            Self::Boolean(v) => write!(f, "{}", v),
+            Self::EmptyList => write!(f, "[]"),
+            Self::EnumVariant { enum_name, variant } => write!(f, "{}.{}", enum_name, variant),
+        }
+    }
+}
+
+impl Literal {
+    // checks literal can be used as a value of `value_type`
+    pub fn check_type(&self, value_type: &ValueType, spec: &LeapSpec) -> Result<(), String> {
+        let is_valid = match (self, value_type) {
+            (Self::Integer(v), ValueType::Simple(t, _)) => match t {
+                SimpleType::Integer
+                | SimpleType::Int64
+                | SimpleType::Float
+                | SimpleType::Decimal => true,
+                SimpleType::Int8 => i8::try_from(*v).is_ok(),
+                SimpleType::Int16 => i16::try_from(*v).is_ok(),
+                SimpleType::Int32 => i32::try_from(*v).is_ok(),
+                SimpleType::UInt8 => u8::try_from(*v).is_ok(),
+                SimpleType::UInt16 => u16::try_from(*v).is_ok(),
+                SimpleType::UInt32 => u32::try_from(*v).is_ok(),
+                SimpleType::UInt64 => u64::try_from(*v).is_ok(),
+                _ => false,
+            },
+            (Self::Float(_), ValueType::Simple(t, _)) => {
+                matches!(t, SimpleType::Float | SimpleType::Decimal)
+            }
+            (Self::String(v), ValueType::Simple(t, _)) => match t {
+                SimpleType::String => true,
+                SimpleType::Decimal => is_decimal(v),
+                SimpleType::Date => is_date(v),
+                SimpleType::DateTime => is_datetime(v),
+                SimpleType::Duration => is_duration(v),
+                SimpleType::Uuid => is_uuid(v),
+                _ => false,
+            },
+            (Self::Boolean(_), ValueType::Simple(t, _)) => matches!(t, SimpleType::Boolean),
+            (Self::EmptyList, ValueType::List(..)) => true,
+            (Self::EnumVariant { enum_name, variant }, ValueType::LeapType { name, .. }) => {
+                if enum_name != name {
+                    return Err(format!(
+                        "Enum `{}` doesn't match type `{}`",
+                        enum_name, value_type
+                    ));
+                }
+                match spec.get_type_by_name(name.get()).and_then(|t| t.as_enum()) {
+                    Some(e) => {
+                        if !e.variants.iter().any(|v| &v.name == variant) {
+                            return Err(format!(
+                                "Enum `{}` doesn't have variant `{}`",
+                                enum_name, variant
+                            ));
+                        }
+                        true
+                    }
+                    None => return Err(format!("Unknown enum `{}`", enum_name)),
+                }
+            }
+            (_, ValueType::TypeArg(_)) => {
+                return Err("Type argument can't have default value".to_owned())
+            }
+            _ => false,
+        };
+        if is_valid {
+            Ok(())
+        } else {
+            Err(format!(
+                "Value `{}` can't be used for type `{}`",
+                self, value_type
+            ))
+        }
+    }
+}
+
+fn is_digits(s: &str, count: usize) -> bool {
+    s.len() == count && s.chars().all(|c| c.is_ascii_digit())
+}
+
+// `-12.50`
+fn is_decimal(s: &str) -> bool {
+    let s = s.strip_prefix('-').unwrap_or(s);
+    let (int, fraction) = s.split_once('.').unwrap_or((s, "0"));
+    [int, fraction]
+        .iter()
+        .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+// `2024-02-29`
+fn is_date(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    if parts.len() != 3
+        || !is_digits(parts[0], 4)
+        || !is_digits(parts[1], 2)
+        || !is_digits(parts[2], 2)
+    {
+        return false;
+    }
+    let [year, month, day] = [parts[0], parts[1], parts[2]].map(|p| p.parse::<u32>().unwrap());
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 0,
+    };
+    (1..=days).contains(&day)
+}
+
+// `12:30:00.5`, seconds up to 60 for leap seconds
+fn is_time(s: &str) -> bool {
+    let (s, fraction) = s.split_once('.').unwrap_or((s, "0"));
+    let parts: Vec<&str> = s.split(':').collect();
+    parts.len() == 3
+        && parts.iter().all(|p| is_digits(p, 2))
+        && !fraction.is_empty()
+        && fraction.chars().all(|c| c.is_ascii_digit())
+        && parts[0] < "24"
+        && parts[1] < "60"
+        && parts[2] <= "60"
+}
+
+// rfc 3339, `2024-02-29T12:30:00Z` or `2024-02-29T12:30:00+02:00`
+fn is_datetime(s: &str) -> bool {
+    let Some((date, time)) = s.split_once(['T', 't']) else {
+        return false;
+    };
+    let time = match time.strip_suffix(['Z', 'z']) {
+        Some(time) => time,
+        None => {
+            let Some(i) = time.rfind(['+', '-']) else {
+                return false;
+            };
+            let offset: Vec<&str> = time[i + 1..].split(':').collect();
+            if offset.len() != 2
+                || !offset.iter().all(|p| is_digits(p, 2))
+                || offset[0] >= "24"
+                || offset[1] >= "60"
+            {
+                return false;
+            }
+            &time[..i]
+        }
+    };
+    is_date(date) && is_time(time)
+}
+
+// iso 8601, `P1Y2M3DT4H5M6.5S` or `P2W`
+fn is_duration(s: &str) -> bool {
+    let Some(s) = s.strip_prefix('P') else {
+        return false;
+    };
+    let (date, time) = match s.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (s, None),
+    };
+    // components are numbers followed by units in the given order, seconds can have fraction
+    let is_components = |s: &str, units: &str| {
+        let mut units = units.chars();
+        let mut number = String::new();
+        for c in s.chars() {
+            if c.is_ascii_digit() || c == '.' {
+                number.push(c);
+            } else if !units.any(|u| u == c)
+                || !is_decimal(&number)
+                || (c != 'S' && number.contains('.'))
+            {
+                return false;
+            } else {
+                number.clear();
+            }
+        }
+        number.is_empty()
+    };
+    is_components(date, "YMWD")
+        && time.is_none_or(|t| !t.is_empty() && is_components(t, "HMS"))
+        && (!date.is_empty() || time.is_some())
+}
+
+// `123e4567-e89b-12d3-a456-426614174000`
+fn is_uuid(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('-').collect();
+    parts.iter().map(|p| p.len()).eq([8, 4, 4, 4, 12])
+        && parts
+            .iter()
+            .all(|p| p.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+impl fmt::Display for Attribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "@{} {}", self.name, value),
+            None => write!(f, "@{}", self.name),
         }
     }
 }
@@ -195,15 +580,10 @@ impl SimpleType {
 impl fmt::Display for ValueType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            Self::Simple(t) => match t {
-                SimpleType::String => write!(f, "str"),
-                SimpleType::Integer => write!(f, "int"),
-                SimpleType::Float => write!(f, "float"),
-                SimpleType::Boolean => write!(f, "bool"),
-            },
-            Self::List(t) => write!(f, "list[{}]", t),
+            Self::Simple(t, _) => write!(f, "{}", t.as_str()),
+            Self::List(t, _) => write!(f, "list[{}]", t),
             Self::TypeArg(n) => write!(f, "{}?", n),
-            Self::LeapType { name, args } => {
+            Self::LeapType { name, args, .. } => {
                 if args.is_empty() {
                     write!(f, "{}", name)
                 } else {
@@ -219,26 +599,75 @@ impl fmt::Display for ValueType {
     }
 }
 
+impl PartialEq for ValueType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Simple(a, _), Self::Simple(b, _)) => a == b,
+            (Self::List(a, _), Self::List(b, _)) => a == b,
+            (Self::TypeArg(a), Self::TypeArg(b)) => a == b,
+            (
+                Self::LeapType { name, args, .. },
+                Self::LeapType {
+                    name: other_name,
+                    args: other_args,
+                    ..
+                },
+            ) => name == other_name && args == other_args,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for ValueType {}
+
+impl Hash for ValueType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Self::Simple(t, _) => t.hash(state),
+            Self::List(t, _) => t.hash(state),
+            Self::TypeArg(n) => n.hash(state),
+            Self::LeapType { name, args, .. } => {
+                name.hash(state);
+                args.hash(state);
+            }
+        }
+    }
+}
+
 impl ValueType {
     pub fn to_aliased(&self, aliases: &HashMap<String, String>) -> Result<Self, String> {
         match self {
-            Self::List(t) => Ok(Self::List(Box::new(t.to_aliased(aliases)?))),
+            Self::List(t, position) => Ok(Self::List(Box::new(t.to_aliased(aliases)?), *position)),
             Self::TypeArg(n) => Ok(Self::TypeArg(aliased_from_aliases(n, aliases)?)),
-            Self::LeapType { name, args } => Ok(Self::LeapType {
+            Self::LeapType {
+                name,
+                args,
+                position,
+            } => Ok(Self::LeapType {
                 name: aliased_from_aliases(name, aliases)?,
                 args: args
                     .iter()
                     .map(|a| a.to_aliased(aliases))
                     .collect::<Result<_, _>>()?,
+                position: *position,
             }),
             _ => Ok(self.clone()),
         }
     }
 
+    pub fn position(&self) -> &Position {
+        match self {
+            Self::Simple(_, position) | Self::List(_, position) => position,
+            Self::TypeArg(n) => &n.position,
+            Self::LeapType { position, .. } => position,
+        }
+    }
+
     pub fn name(&self) -> String {
         match self {
-            Self::Simple(t) => t.name(),
-            Self::List(_) => "list".to_owned(),
+            Self::Simple(t, _) => t.name(),
+            Self::List(..) => "list".to_owned(),
             Self::TypeArg(n) => n.get().to_owned(),
             Self::LeapType { name, .. } => name.get().to_owned(),
         }
@@ -246,20 +675,25 @@ impl ValueType {
 
     pub fn args(&self) -> Vec<ValueType> {
         match self {
-            Self::Simple(_) | Self::TypeArg(_) => vec![],
-            Self::List(t) => vec![t.as_ref().clone()],
+            Self::Simple(..) | Self::TypeArg(_) => vec![],
+            Self::List(t, _) => vec![t.as_ref().clone()],
             Self::LeapType { args, .. } => args.clone(),
         }
     }
 
     pub fn apply_args(&self, applied_args: &HashMap<&Name, &ValueType>) -> Self {
         match self {
-            Self::Simple(_) => self.clone(),
-            Self::List(t) => Self::List(Box::new(t.apply_args(applied_args))),
+            Self::Simple(..) => self.clone(),
+            Self::List(t, position) => Self::List(Box::new(t.apply_args(applied_args)), *position),
             Self::TypeArg(name) => (*applied_args.get(name).unwrap()).clone(),
-            Self::LeapType { name, args } => Self::LeapType {
+            Self::LeapType {
+                name,
+                args,
+                position,
+            } => Self::LeapType {
                 name: name.clone(),
                 args: args.iter().map(|a| a.apply_args(applied_args)).collect(),
+                position: *position,
             },
         }
     }
@@ -267,15 +701,26 @@ impl ValueType {
 
 impl fmt::Display for Prop {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.name, self.prop_type)
+        write!(f, "{}: {}", self.name, self.prop_type)?;
+        if let Some(default) = &self.default {
+            write!(f, " = {}", default)?;
+        }
+        Ok(())
     }
 }
 
 impl Prop {
+    pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+
     pub fn to_aliased(&self, aliases: &HashMap<String, String>) -> Result<Self, String> {
         Ok(Self {
             name: aliased_from_aliases(&self.name, aliases)?,
             prop_type: self.prop_type.to_aliased(aliases)?,
+            default: self.default.clone(),
+            attributes: self.attributes.clone(),
+            doc: self.doc.clone(),
             position: self.position,
             is_recursive: self.is_recursive,
         })
@@ -285,6 +730,9 @@ impl Prop {
         Self {
             name: self.name.clone(),
             prop_type: self.prop_type.apply_args(applied_args),
+            default: self.default.clone(),
+            attributes: self.attributes.clone(),
+            doc: self.doc.clone(),
             position: self.position,
             is_recursive: self.is_recursive,
         }
@@ -327,12 +775,17 @@ impl LeapStruct {
                 .iter()
                 .map(|p| p.to_aliased(aliases))
                 .collect::<Result<_, _>>()?,
+            attributes: self.attributes.clone(),
+            doc: self.doc.clone(),
             path: self.path.clone(),
             position: self.position,
         })
     }
 
-    pub fn map_args<'a>(&'a self, applied_args: &'a [ValueType]) -> HashMap<&Name, &ValueType> {
+    pub fn map_args<'a>(
+        &'a self,
+        applied_args: &'a [ValueType],
+    ) -> HashMap<&'a Name, &'a ValueType> {
         let mut args_map = HashMap::new();
         for (i, name) in self.args.iter().enumerate() {
             // applied_args should have same length as self.args
@@ -351,6 +804,8 @@ impl LeapStruct {
                 .iter()
                 .map(|p| p.apply_args(applied_args))
                 .collect(),
+            attributes: self.attributes.clone(),
+            doc: self.doc.clone(),
             path: self.path.clone(),
             position: self.position,
         }
@@ -400,6 +855,8 @@ impl LeapEnum {
                 .iter()
                 .map(|v| v.to_aliased(aliases))
                 .collect::<Result<_, _>>()?,
+            attributes: self.attributes.clone(),
+            doc: self.doc.clone(),
             path: self.path.clone(),
             position: self.position,
         })
@@ -412,7 +869,10 @@ impl LeapEnum {
             .collect()
     }
 
-    pub fn map_args<'a>(&'a self, applied_args: &'a [ValueType]) -> HashMap<&Name, &ValueType> {
+    pub fn map_args<'a>(
+        &'a self,
+        applied_args: &'a [ValueType],
+    ) -> HashMap<&'a Name, &'a ValueType> {
         let mut args_map = HashMap::new();
         for (i, name) in self.args.iter().enumerate() {
             // applied_args should have same length as self.args
@@ -431,6 +891,8 @@ impl LeapEnum {
                 .iter()
                 .map(|v| v.apply_args(applied_args))
                 .collect(),
+            attributes: self.attributes.clone(),
+            doc: self.doc.clone(),
             path: self.path.clone(),
             position: self.position,
         }
@@ -492,6 +954,24 @@ impl LeapType {
         }
     }
 
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Self::Enum(e) => &e.attributes,
+            Self::Struct(s) => &s.attributes,
+        }
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes().iter().find(|a| a.name == name)
+    }
+
+    pub fn doc(&self) -> Option<&str> {
+        match self {
+            Self::Enum(e) => e.doc.as_deref(),
+            Self::Struct(s) => s.doc.as_deref(),
+        }
+    }
+
     pub fn path(&self) -> &str {
         match self {
             Self::Enum(e) => &e.path,
@@ -528,6 +1008,89 @@ impl LeapType {
     }
 }
 
+impl fmt::Display for LeapConst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Const({}: {} = {})",
+            self.name, self.value_type, self.value
+        )
+    }
+}
+
+impl LeapConst {
+    pub fn to_aliased(&self, aliases: &HashMap<String, String>) -> Result<Self, String> {
+        Ok(Self {
+            name: aliased_from_aliases(&self.name, aliases)?,
+            value_type: self.value_type.to_aliased(aliases)?,
+            value: self.value.clone(),
+            attributes: self.attributes.clone(),
+            doc: self.doc.clone(),
+            path: self.path.clone(),
+            position: self.position,
+        })
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&Attribute> {
+        self.attributes.iter().find(|a| a.name == name)
+    }
+}
+
+impl fmt::Display for LeapDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Type(t) => write!(f, "{}", t),
+            Self::Const(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+impl LeapDefinition {
+    pub fn as_type(&self) -> Option<&LeapType> {
+        if let Self::Type(t) = self {
+            Some(t)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_const(&self) -> Option<&LeapConst> {
+        if let Self::Const(c) = self {
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    pub fn name(&self) -> &Name {
+        match self {
+            Self::Type(t) => t.name(),
+            Self::Const(c) => &c.name,
+        }
+    }
+
+    pub fn attributes(&self) -> &[Attribute] {
+        match self {
+            Self::Type(t) => t.attributes(),
+            Self::Const(c) => &c.attributes,
+        }
+    }
+
+    pub fn position(&self) -> &Position {
+        match self {
+            Self::Type(t) => t.position(),
+            Self::Const(c) => &c.position,
+        }
+    }
+
+    pub fn set_path(&mut self, path: String) {
+        match self {
+            Self::Type(t) => t.set_path(path),
+            Self::Const(c) => c.path = path,
+        }
+    }
+}
+
 impl fmt::Display for LeapSpec {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
@@ -536,6 +1099,7 @@ impl fmt::Display for LeapSpec {
             self.types
                 .iter()
                 .map(|t| format!("{}", t))
+                .chain(self.consts.iter().map(|c| format!("{}", c)))
                 .collect::<Vec<_>>()
                 .join(", ")
         )
@@ -551,11 +1115,86 @@ impl IntoIterator for LeapSpec {
     }
 }
 
+// spec is stored as lists of types and constants, indexes by name are rebuilt on deserialization
+#[cfg(feature = "serde")]
+impl serde::Serialize for LeapSpec {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("LeapSpec", 2)?;
+        state.serialize_field("types", &self.types)?;
+        state.serialize_field("consts", &self.consts)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LeapSpec {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Spec {
+            types: Vec<LeapType>,
+            consts: Vec<LeapConst>,
+        }
+        let spec = Spec::deserialize(deserializer)?;
+        let definitions = spec
+            .types
+            .into_iter()
+            .map(LeapDefinition::Type)
+            .chain(spec.consts.into_iter().map(LeapDefinition::Const))
+            .collect();
+        Ok(Self::from_definitions(definitions))
+    }
+}
+
+// finds references to type by name
+// renames references to type in value types and enum values
+struct RenameReferences<'a> {
+    type_name: &'a str,
+    new_name: &'a str,
+    // enums with variants named by the type (`page[int]`), the variants are renamed too
+    variant_owners: Vec<String>,
+}
+
+impl VisitorMut for RenameReferences<'_> {
+    fn visit_enum_mut(&mut self, leap_enum: &mut LeapEnum) {
+        if self.variant_owners.contains(&leap_enum.name.name) {
+            for v in &mut leap_enum.variants {
+                if v.name.get() == self.type_name {
+                    v.name.name = self.new_name.to_owned();
+                }
+            }
+        }
+        walk_enum_mut(self, leap_enum);
+    }
+
+    fn visit_value_type_mut(&mut self, value_type: &mut ValueType) {
+        if let ValueType::LeapType { name, .. } = value_type {
+            if name.get() == self.type_name {
+                name.name = self.new_name.to_owned();
+            }
+        }
+        walk_value_type_mut(self, value_type);
+    }
+
+    fn visit_literal_mut(&mut self, literal: &mut Literal) {
+        if let Literal::EnumVariant { enum_name, variant } = literal {
+            if variant.get() == self.type_name && self.variant_owners.contains(&enum_name.name) {
+                variant.name = self.new_name.to_owned();
+            }
+            if enum_name.get() == self.type_name {
+                enum_name.name = self.new_name.to_owned();
+            }
+        }
+    }
+}
+
 impl LeapSpec {
     pub fn new(types: Vec<LeapType>) -> Self {
         let mut spec = Self {
             types: vec![],
             name_to_type: HashMap::new(),
+            consts: vec![],
+            name_to_const: HashMap::new(),
         };
         for leap_type in types.into_iter() {
             spec.push_type(leap_type);
@@ -563,6 +1202,17 @@ impl LeapSpec {
         spec
     }
 
+    pub fn from_definitions(definitions: Vec<LeapDefinition>) -> Self {
+        let mut spec = Self::new(vec![]);
+        for definition in definitions.into_iter() {
+            match definition {
+                LeapDefinition::Type(t) => spec.push_type(t),
+                LeapDefinition::Const(c) => spec.push_const(c),
+            }
+        }
+        spec
+    }
+
     fn push_type(&mut self, leap_type: LeapType) {
         let name = leap_type.name().get().to_owned();
         self.types.push(leap_type);
@@ -570,6 +1220,20 @@ impl LeapSpec {
             .insert(name, LeapTypeHandle::new((self.types.len() - 1) as u32));
     }
 
+    fn push_const(&mut self, leap_const: LeapConst) {
+        let name = leap_const.name.get().to_owned();
+        self.consts.push(leap_const);
+        self.name_to_const.insert(name, self.consts.len() - 1);
+    }
+
+    pub fn iter_consts(&self) -> impl Iterator<Item = &LeapConst> {
+        self.consts.iter()
+    }
+
+    pub fn get_const_by_name(&self, name: &str) -> Option<&LeapConst> {
+        self.name_to_const.get(name).map(|i| &self.consts[*i])
+    }
+
     pub fn iter_type_refs(&self) -> impl Iterator<Item = &LeapType> {
         self.types.iter()
     }
@@ -580,15 +1244,19 @@ impl LeapSpec {
 
     pub fn join(&mut self, other: LeapSpec) {
         // todo: consume self, and return new spec? so new spec always created with `new`
-        for leap_type in other.into_iter() {
+        for leap_type in other.types.into_iter() {
             self.push_type(leap_type);
         }
+        for leap_const in other.consts.into_iter() {
+            self.push_const(leap_const);
+        }
     }
 
     pub fn get_type_ref(&self, handle: LeapTypeHandle) -> &LeapType {
         &self.types[handle.as_index()]
     }
 
+    // name of the type should be changed with `rename_type`, to keep index by name consistent
     pub fn get_type_mut(&mut self, handle: LeapTypeHandle) -> &mut LeapType {
         &mut self.types[handle.as_index()]
     }
@@ -618,15 +1286,150 @@ impl LeapSpec {
     }
 
     pub fn to_aliased(&self, aliases: &HashMap<String, String>) -> Result<Self, String> {
-        Ok(Self::new(
+        let mut spec = Self::new(
             self.types
                 .iter()
                 .map(|t| t.to_aliased(aliases))
                 .collect::<Result<_, _>>()?,
-        ))
+        );
+        for c in &self.consts {
+            spec.push_const(c.to_aliased(aliases)?);
+        }
+        Ok(spec)
+    }
+
+    pub fn check_defaults(&self) -> Result<(), PathError> {
+        for t in self.iter_type_refs() {
+            if let LeapType::Struct(s) = t {
+                for p in &s.props {
+                    if let Some(default) = &p.default {
+                        default
+                            .check_type(&p.prop_type, self)
+                            .map_err(|e| PathError::new(e, s.path.clone(), p.position.start))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_consts(&self) -> Result<(), PathError> {
+        for c in &self.consts {
+            c.value
+                .check_type(&c.value_type, self)
+                .map_err(|e| PathError::new(e, c.path.clone(), c.position.start))?;
+        }
+        Ok(())
+    }
+
+    // adds type, fails if type with the same name exists
+    pub fn add_type(&mut self, leap_type: LeapType) -> Result<LeapTypeHandle, String> {
+        let name = leap_type.name().get();
+        if self.name_to_type.contains_key(name) {
+            return Err(format!("Type `{}` already exists", name));
+        }
+        self.push_type(leap_type);
+        self.mark_recursive_props();
+        Ok(LeapTypeHandle::new((self.types.len() - 1) as u32))
+    }
+
+    // removes type by name, handles of types after the removed one are shifted,
+    // references to the removed type are kept
+    pub fn remove_type(&mut self, name: &str) -> Option<LeapType> {
+        let handle = self.get_handle_by_name(name)?;
+        let leap_type = self.types.remove(handle.as_index());
+        self.rebuild_index();
+        self.mark_recursive_props();
+        Some(leap_type)
     }
 
+    // replaces type with the same name, returns replaced type
+    pub fn replace_type(&mut self, leap_type: LeapType) -> Result<LeapType, String> {
+        let name = leap_type.name().get();
+        let handle = self
+            .get_handle_by_name(name)
+            .ok_or_else(|| format!("Type `{}` not found", name))?;
+        let replaced = mem::replace(self.get_type_mut(handle), leap_type);
+        self.mark_recursive_props();
+        Ok(replaced)
+    }
+
+    // renames type, with `rewrite_references` also renames references in props, constants
+    // and enum values, otherwise references are kept as is
+    pub fn rename_type(
+        &mut self,
+        name: &str,
+        new_name: &str,
+        rewrite_references: bool,
+    ) -> Result<(), String> {
+        rename::check_name(new_name)?;
+        let handle = self
+            .get_handle_by_name(name)
+            .ok_or_else(|| format!("Type `{}` not found", name))?;
+        rename::check_new_type_name(self, new_name)?;
+        let renamed = |n: &Name| Name {
+            name: new_name.to_owned(),
+            ..n.clone()
+        };
+        if rewrite_references {
+            let variant_owners = rename::check_type_references(self, name, new_name)?
+                .into_iter()
+                .map(|(_, owner, _)| owner.name.name.clone())
+                .collect();
+            let mut rename = RenameReferences {
+                type_name: name,
+                new_name,
+                variant_owners,
+            };
+            self.types.iter_mut().for_each(|t| rename.visit_type_mut(t));
+            self.consts
+                .iter_mut()
+                .for_each(|c| rename.visit_const_mut(c));
+        }
+        match self.get_type_mut(handle) {
+            LeapType::Struct(s) => s.name = renamed(&s.name),
+            LeapType::Enum(e) => e.name = renamed(&e.name),
+        }
+        self.rebuild_index();
+        self.mark_recursive_props();
+        Ok(())
+    }
+
+    pub(crate) fn rebuild_index(&mut self) {
+        self.name_to_type = self
+            .types
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (t.name().get().to_owned(), LeapTypeHandle::new(i as u32)))
+            .collect();
+        self.name_to_const = self
+            .consts
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (c.name.get().to_owned(), i))
+            .collect();
+    }
+
+    // index should be rebuilt after names are changed
+    pub(crate) fn types_mut(&mut self) -> &mut [LeapType] {
+        &mut self.types
+    }
+
+    pub(crate) fn consts_mut(&mut self) -> &mut [LeapConst] {
+        &mut self.consts
+    }
+
+    // flags are recalculated from scratch, so it can be called again after changes of spec
     pub fn mark_recursive_props(&mut self) {
+        for t in &mut self.types {
+            let props = match t {
+                LeapType::Struct(s) => &mut s.props,
+                LeapType::Enum(e) => &mut e.variants,
+            };
+            for p in props {
+                p.is_recursive = false;
+            }
+        }
         for h in self.iter_types() {
             let mut recursive_props = vec![];
             let t = self.get_type_ref(h);
@@ -661,6 +1464,7 @@ impl LeapSpec {
 
 #[cfg(test)]
 mod test {
+    use crate::parser::itemposition::ItemPosition;
     use crate::parser::parser::Parser;
 
     use super::*;
@@ -684,4 +1488,208 @@ mod test {
         assert!(s.props[0].is_recursive);
         assert!(!s.props[1].is_recursive);
     }
+
+    #[test]
+    fn test_mutations() {
+        let spec_text = "
+            .struct s1
+                a: s2
+                b: list[s3]
+            .struct s2
+                a: s1
+            .enum s3
+                s1
+            .struct s4[t]
+                a: t
+                e: s3 = s3.s1
+            .const c: s3 = s3.s1
+        ";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(spec_text).unwrap());
+        spec.mark_recursive_props();
+        let is_recursive = |spec: &LeapSpec, name: &str| {
+            let s = spec.get_type_by_name(name).unwrap().as_struct().unwrap();
+            s.props[0].is_recursive
+        };
+        assert!(is_recursive(&spec, "s1"));
+        let s2 = spec.remove_type("s2").unwrap();
+        assert!(spec.get_type_by_name("s2").is_none());
+        assert_eq!(spec.get_type_by_name("s4").unwrap().name().get(), "s4");
+        assert!(!is_recursive(&spec, "s1"));
+        assert_eq!(
+            spec.add_type(s2.clone()).unwrap(),
+            spec.get_handle_by_name("s2").unwrap()
+        );
+        assert!(is_recursive(&spec, "s1"));
+        assert_eq!(spec.add_type(s2).unwrap_err(), "Type `s2` already exists");
+        let replacement = Parser::parse(".struct s2 a: int").unwrap().remove(0);
+        assert_eq!(spec.replace_type(replacement).unwrap().name().get(), "s2");
+        assert!(!is_recursive(&spec, "s1"));
+        assert_eq!(
+            spec.rename_type("s3", "t", true).unwrap_err(),
+            "Type argument `t` of `s4` conflicts with the new name"
+        );
+        assert_eq!(
+            spec.rename_type("s3", "s1", true).unwrap_err(),
+            "Type `s1` already exists"
+        );
+        assert_eq!(
+            spec.rename_type("s3", "int", true).unwrap_err(),
+            "Builtin type `int` already exists"
+        );
+        spec.rename_type("s3", "e3", true).unwrap();
+        assert!(spec.get_type_by_name("s3").is_none());
+        assert!(spec.is_enum_name("e3"));
+        let s4 = spec.get_type_by_name("s4").unwrap().as_struct().unwrap();
+        assert_eq!(s4.props[1].prop_type.name(), "e3");
+        assert_eq!(s4.props[1].default.as_ref().unwrap().to_string(), "e3.s1");
+        assert_eq!(
+            spec.get_const_by_name("c").unwrap().value.to_string(),
+            "e3.s1"
+        );
+        assert!(spec.check_defaults().is_ok());
+        assert!(spec.check_consts().is_ok());
+        assert_eq!(
+            spec.rename_type("s1", "c", true).unwrap_err(),
+            "Constant `c` already exists"
+        );
+        // variant named by the type is renamed with it, same as by `rename::rename`
+        spec.rename_type("s1", "r1", true).unwrap();
+        let e3 = spec.get_type_by_name("e3").unwrap().as_enum().unwrap();
+        assert_eq!(e3.variants[0].name.get(), "r1");
+        assert_eq!(e3.variants[0].prop_type.name(), "r1");
+        assert_eq!(
+            spec.get_const_by_name("c").unwrap().value.to_string(),
+            "e3.r1"
+        );
+        assert!(spec.check_consts().is_ok());
+        spec.rename_type("r1", "s1", true).unwrap();
+        spec.rename_type("s1", "r1", false).unwrap();
+        let e3 = spec.get_type_by_name("e3").unwrap().as_enum().unwrap();
+        assert_eq!(e3.variants[0].prop_type.name(), "s1");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let spec_text = ".struct s1[t]\n    a: list[t]\n    b: e1 = e1.s1\n.enum e1\n    s1[int]\n.const c: int = 1";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(spec_text).unwrap());
+        spec.mark_recursive_props();
+        let json = serde_json::to_string(&spec).unwrap();
+        let spec: LeapSpec = serde_json::from_str(&json).unwrap();
+        assert!(spec.is_enum_name("e1"));
+        assert!(spec.get_const_by_name("c").is_some());
+        assert_eq!(serde_json::to_string(&spec).unwrap(), json);
+        // representation is stable, see README
+        let spec = LeapSpec::new(Parser::parse(".struct a x: list[int]").unwrap());
+        assert_eq!(
+            serde_json::to_string(&spec).unwrap(),
+            r#"{"types":[{"struct":{"name":{"name":"a","alias":null,"position":{"start":8,"length":1}},"args":[],"props":[{"name":{"name":"x","alias":null,"position":{"start":10,"length":1}},"prop_type":{"list":[{"simple":["int",{"start":18,"length":3}]},{"start":13,"length":9}]},"default":null,"attributes":[],"doc":null,"position":{"start":10,"length":12},"is_recursive":false}],"attributes":[],"doc":null,"path":"","position":{"start":0,"length":22}}}],"consts":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_check_defaults() {
+        // values which don't depend on other files are checked by parser too
+        let check = |text| {
+            let spec = LeapSpec::new(Parser::parse(text).map_err(|e| e.1)?);
+            spec.check_defaults().map_err(|e| e.error)
+        };
+        assert!(
+            check(".struct s a: int = 3 b: float = 3 c: u8 = 255 d: date = \"2020-01-01\"").is_ok()
+        );
+        assert!(check(".struct s a: list[s] = [] b: e = e.s2 .enum e s s2: s").is_ok());
+        assert!(check(".struct s a: u8 = 256").is_err());
+        assert!(check(".struct s a: u64 = -1").is_err());
+        assert!(check(".struct s a: int = 1.5").is_err());
+        assert!(check(".struct s a: str = true").is_err());
+        assert!(check(".struct s a: bool = []").is_err());
+        assert!(check(".struct s a: e = e.s3 .enum e s").is_err());
+        assert!(check(".struct s a: e = ee.s .enum e s").is_err());
+        assert!(check(".struct s a: e = e.s").is_err());
+        assert!(check(".struct s[t] a: t = 1").is_err());
+        assert!(check(".struct s a: i8 = -128 b: i16 = 32767 c: u32 = 4294967295").is_ok());
+        assert!(check(".struct s a: i8 = -129").is_err());
+        assert!(check(".struct s a: u32 = 4294967296").is_err());
+        assert!(check(
+            ".struct s a: date = \"2024-02-29\" b: uuid = \"123e4567-e89b-12d3-a456-426614174000\""
+        )
+        .is_ok());
+        assert!(check(
+            ".struct s a: datetime = \"2024-02-29T12:30:00.5+02:00\" b: duration = \"P1DT2H30.5S\""
+        )
+        .is_ok());
+        assert!(
+            check(".struct s a: decimal = \"-12.50\" b: datetime = \"2024-02-29t12:30:00Z\"")
+                .is_ok()
+        );
+        assert!(check(".struct s a: date = \"2023-02-29\"").is_err());
+        assert!(check(".struct s a: date = \"2020-1-01\"").is_err());
+        assert!(check(".struct s a: datetime = \"2020-01-01 12:00:00Z\"").is_err());
+        assert!(check(".struct s a: datetime = \"2020-01-01T24:00:00Z\"").is_err());
+        assert!(check(".struct s a: duration = \"PT\"").is_err());
+        assert!(check(".struct s a: duration = \"P1H\"").is_err());
+        assert!(check(".struct s a: uuid = \"123e4567e89b12d3a456426614174000\"").is_err());
+        assert!(check(".struct s a: decimal = \"1e5\"").is_err());
+    }
+
+    #[test]
+    fn test_consts() {
+        let spec = LeapSpec::from_definitions(
+            Parser::parse_definitions(
+                ".const max-page-size: int = 100 .const mode: e = e.s .enum e s .struct s",
+            )
+            .unwrap(),
+        );
+        assert!(spec.check_consts().is_ok());
+        let c = spec.get_const_by_name("max-page-size").unwrap();
+        assert_eq!(c.value, Literal::Integer(100));
+        assert_eq!(
+            spec.iter_consts().map(|c| c.name.get()).collect::<Vec<_>>(),
+            vec!["max-page-size", "mode"]
+        );
+        assert!(spec.get_const_by_name("e").is_none());
+        assert_eq!(
+            Parser::parse_definitions(".const a: u8 = 1000").unwrap_err(),
+            ItemPosition(
+                Position::new(0, 19),
+                "Value `1000` can't be used for type `u8`".to_owned()
+            )
+        );
+        let spec =
+            LeapSpec::from_definitions(Parser::parse_definitions(".const a: e = e.s").unwrap());
+        assert!(spec.check_consts().is_err());
+    }
+
+    #[test]
+    fn test_simple_type_names() {
+        for t in SimpleType::ALL {
+            assert_eq!(SimpleType::from_name(&t.name()), Some(t));
+        }
+        assert_eq!(SimpleType::from_name("list"), None);
+    }
+
+    #[test]
+    fn test_value_type_positions() {
+        let types = Parser::parse(".struct a\n    tags: list[strr]\n    p: page[int a]").unwrap();
+        let props = &types[0].as_struct().unwrap().props;
+        let tags = &props[0].prop_type;
+        assert_eq!(tags.position(), &Position::new(20, 10));
+        assert_eq!(tags.args()[0].position(), &Position::new(25, 4));
+        let p = &props[1].prop_type;
+        assert_eq!(p.position(), &Position::new(38, 11));
+        let positions: Vec<_> = p.args().iter().map(|a| *a.position()).collect();
+        assert_eq!(positions, vec![Position::new(43, 3), Position::new(47, 1)]);
+        // positions are ignored in comparison
+        assert_eq!(
+            p.args()[0],
+            ValueType::Simple(SimpleType::Integer, Position::default())
+        );
+        let mut visited = std::collections::HashSet::new();
+        visited.insert(p.args()[1].clone());
+        assert!(!visited.insert(ValueType::LeapType {
+            name: Name::new("a".to_owned(), Position::default()).unwrap(),
+            args: vec![],
+            position: Position::default(),
+        }));
+    }
 }
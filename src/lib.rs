@@ -1,7 +1,16 @@
+pub mod builder;
+pub mod codegen;
+pub mod document;
 pub mod formatter;
 pub mod handle;
 pub mod leaptypes;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod naming;
 pub mod parser;
+pub mod rename;
 pub mod stdtypes;
+pub mod symbols;
+pub mod textedit;
+pub mod visitor;
 mod prop_recursion_check;
\ No newline at end of file
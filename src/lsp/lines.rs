@@ -0,0 +1,66 @@
+// conversion between character positions used by parser and line/character positions of
+// language server protocol, where character is counted in utf-16 code units
+pub struct LineIndex {
+    // lines of text, without line breaks
+    lines: Vec<Vec<char>>,
+    // positions of line starts
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(data: &str) -> Self {
+        let lines: Vec<Vec<char>> = data.split('\n').map(|l| l.chars().collect()).collect();
+        let starts = lines
+            .iter()
+            .scan(0, |start, l| {
+                let line_start = *start;
+                *start += l.len() + 1;
+                Some(line_start)
+            })
+            .collect();
+        LineIndex { lines, starts }
+    }
+
+    pub fn to_line_character(&self, position: usize) -> (usize, usize) {
+        let i = self.starts.partition_point(|s| *s <= position) - 1;
+        let line = &self.lines[i];
+        let end = (position - self.starts[i]).min(line.len());
+        (i, line[..end].iter().map(|c| c.len_utf16()).sum())
+    }
+
+    pub fn to_position(&self, line: usize, character: usize) -> usize {
+        let Some(l) = self.lines.get(line) else {
+            // position after the end of text
+            let last = self.lines.len() - 1;
+            return self.starts[last] + self.lines[last].len();
+        };
+        let start = self.starts[line];
+        let mut utf16 = 0;
+        for (i, c) in l.iter().enumerate() {
+            if utf16 >= character {
+                return start + i;
+            }
+            utf16 += c.len_utf16();
+        }
+        start + l.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_index() {
+        let index = LineIndex::new("ab\n😀c\n");
+        assert_eq!(index.to_line_character(1), (0, 1));
+        assert_eq!(index.to_line_character(3), (1, 0));
+        assert_eq!(index.to_line_character(5), (1, 3));
+        assert_eq!(index.to_line_character(7), (2, 0));
+        assert_eq!(index.to_line_character(100), (2, 0));
+        assert_eq!(index.to_position(1, 2), 4);
+        assert_eq!(index.to_position(1, 3), 5);
+        assert_eq!(index.to_position(0, 10), 2);
+        assert_eq!(index.to_position(2, 0), 6);
+    }
+}
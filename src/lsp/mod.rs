@@ -0,0 +1,2 @@
+mod lines;
+pub mod server;
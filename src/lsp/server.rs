@@ -0,0 +1,693 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead, Write};
+use std::sync::OnceLock;
+
+use crate::document::Document;
+use crate::formatter::{self, FormatOptions};
+use crate::leaptypes::{LeapSpec, LeapType, Name, SimpleType};
+use crate::parser::position::Position;
+use crate::rename;
+use crate::symbols::{self, References, Symbol, SymbolKind};
+use crate::textedit::{self, TextEdit};
+
+use serde_json::{json, Map, Value};
+
+use super::lines::LineIndex;
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REQUEST_FAILED: i64 = -32803;
+
+// language server working over json-rpc, all open documents are treated as one spec
+pub struct Server {
+    // open documents by uri
+    documents: BTreeMap<String, Document>,
+    // line indexes of open documents, rebuilt on every change of document
+    lines: BTreeMap<String, LineIndex>,
+    is_exit: bool,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Server {
+            documents: BTreeMap::new(),
+            lines: BTreeMap::new(),
+            is_exit: false,
+        }
+    }
+
+    // reads messages until `exit` notification or end of input
+    pub fn run<R: BufRead, W: Write>(&mut self, mut reader: R, mut writer: W) -> io::Result<()> {
+        while !self.is_exit {
+            let Some(data) = read_message(&mut reader)? else {
+                break;
+            };
+            let messages = match serde_json::from_str(&data) {
+                Ok(message) => self.handle(&message),
+                Err(e) => vec![error_response(&Value::Null, PARSE_ERROR, &e.to_string())],
+            };
+            for m in messages {
+                write_message(&mut writer, &m)?;
+            }
+        }
+        Ok(())
+    }
+
+    // handles incoming message, returns messages to send back
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let Some(method) = message.get("method").and_then(|m| m.as_str()) else {
+            // responses from client are ignored
+            return vec![];
+        };
+        let params = message.get("params").unwrap_or(&Value::Null);
+        match message.get("id") {
+            Some(id) => vec![match self.handle_request(method, params) {
+                Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                Err((code, error)) => error_response(id, code, &error),
+            }],
+            None => self.handle_notification(method, params),
+        }
+    }
+
+    fn handle_request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    // incremental changes
+                    "textDocumentSync": {"openClose": true, "change": 2},
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "hoverProvider": true,
+                    "renameProvider": true,
+                    "completionProvider": {},
+                    "documentFormattingProvider": true,
+                    "documentRangeFormattingProvider": true,
+                },
+                "serverInfo": {"name": "leap-lsp"},
+            })),
+            "shutdown" => Ok(Value::Null),
+            "textDocument/definition" => {
+                let (uri, offset) = self.text_document_position(params)?;
+                let spec = self.spec();
+                Ok(symbols::symbol_at(&spec, uri, offset)
+                    .and_then(|s| symbols::definition(&spec, uri, &s))
+                    .map_or(Value::Null, |(path, position)| {
+                        self.location(path, position)
+                    }))
+            }
+            "textDocument/references" => {
+                let (uri, offset) = self.text_document_position(params)?;
+                let include_declaration = params
+                    .pointer("/context/includeDeclaration")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true);
+                let spec = self.spec();
+                let Some(symbol) = symbols::symbol_at(&spec, uri, offset) else {
+                    return Ok(json!([]));
+                };
+                let definition = symbols::definition(&spec, uri, &symbol);
+                Ok(Value::Array(
+                    References::new(&spec)
+                        .get(uri, &symbol)
+                        .iter()
+                        .map(|(path, s)| (*path, s.position))
+                        .filter(|r| include_declaration || Some(*r) != definition)
+                        .map(|(path, position)| self.location(path, position))
+                        .collect(),
+                ))
+            }
+            "textDocument/hover" => {
+                let (uri, offset) = self.text_document_position(params)?;
+                let spec = self.spec();
+                Ok(symbols::symbol_at(&spec, uri, offset)
+                    .and_then(|s| Some((hover(&spec, &s)?, s.position)))
+                    .map_or(Value::Null, |(text, position)| {
+                        json!({
+                            "contents": {"kind": "markdown", "value": text},
+                            "range": self.range(uri, position),
+                        })
+                    }))
+            }
+            "textDocument/rename" => {
+                let (uri, offset) = self.text_document_position(params)?;
+                let new_name = params
+                    .get("newName")
+                    .and_then(|n| n.as_str())
+                    .ok_or_else(|| (INVALID_PARAMS, "Expecting new name".to_owned()))?;
+                let edits = rename::rename(&self.spec(), uri, offset, new_name)
+                    .map_err(|e| (REQUEST_FAILED, e))?;
+                let changes: Map<String, Value> = edits
+                    .into_iter()
+                    .map(|(path, edits)| {
+                        let edits = self.text_edits(&path, edits);
+                        (path, edits)
+                    })
+                    .collect();
+                Ok(json!({ "changes": changes }))
+            }
+            "textDocument/completion" => Ok(Value::Array(self.completion())),
+            "textDocument/formatting" => {
+                let document = self.document(params)?;
+                // definitions with errors are left as is
+                let (formatted, _) =
+                    formatter::format_tolerant(document.data(), &format_options(params));
+                Ok(self.text_edits(document.path(), textedit::diff(document.data(), &formatted)))
+            }
+            "textDocument/rangeFormatting" => {
+                let document = self.document(params)?;
+                let range = params
+                    .get("range")
+                    .and_then(|r| range_to_position(self.line_index(document.path()), r))
+                    .ok_or_else(|| (INVALID_PARAMS, "Invalid range".to_owned()))?;
+                let edits =
+                    formatter::format_range(document.data(), range, &format_options(params))
+                        .map_err(|e| (REQUEST_FAILED, e.1))?;
+                Ok(self.text_edits(document.path(), edits))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method `{}`", method))),
+        }
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(|u| u.as_str())
+            .unwrap_or("")
+            .to_owned();
+        match method {
+            "exit" => {
+                self.is_exit = true;
+                vec![]
+            }
+            "textDocument/didOpen" => {
+                let text = params
+                    .pointer("/textDocument/text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("");
+                self.documents
+                    .insert(uri.clone(), Document::new(&uri, text));
+                self.lines.insert(uri, LineIndex::new(text));
+                self.publish_diagnostics(None)
+            }
+            "textDocument/didChange" => {
+                let (Some(document), Some(lines)) =
+                    (self.documents.get_mut(&uri), self.lines.get_mut(&uri))
+                else {
+                    return vec![];
+                };
+                let changes = params.get("contentChanges").and_then(|c| c.as_array());
+                for change in changes.into_iter().flatten() {
+                    let text = change.get("text").and_then(|t| t.as_str()).unwrap_or("");
+                    match change
+                        .get("range")
+                        .and_then(|r| range_to_position(lines, r))
+                    {
+                        Some(position) => {
+                            document.edit(&TextEdit::new(position, text.to_owned()));
+                        }
+                        None => *document = Document::new(&uri, text),
+                    }
+                    *lines = LineIndex::new(document.data());
+                }
+                self.publish_diagnostics(None)
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                self.lines.remove(&uri);
+                self.publish_diagnostics(Some(&uri))
+            }
+            _ => vec![],
+        }
+    }
+
+    fn document(&self, params: &Value) -> Result<&Document, (i64, String)> {
+        params
+            .pointer("/textDocument/uri")
+            .and_then(|u| u.as_str())
+            .and_then(|u| self.documents.get(u))
+            .ok_or_else(|| (INVALID_PARAMS, "Unknown document".to_owned()))
+    }
+
+    // uri of document and character position in it
+    fn text_document_position(&self, params: &Value) -> Result<(&str, usize), (i64, String)> {
+        let document = self.document(params)?;
+        let line = params.pointer("/position/line").and_then(as_usize);
+        let character = params.pointer("/position/character").and_then(as_usize);
+        match (line, character) {
+            (Some(line), Some(character)) => Ok((
+                document.path(),
+                self.line_index(document.path())
+                    .to_position(line, character),
+            )),
+            _ => Err((INVALID_PARAMS, "Invalid position".to_owned())),
+        }
+    }
+
+    // spec made from all open documents, paths of types are uris of documents
+    fn spec(&self) -> LeapSpec {
+        LeapSpec::from_definitions(
+            self.documents
+                .values()
+                .flat_map(|d| d.definitions().cloned())
+                .collect(),
+        )
+    }
+
+    fn completion(&self) -> Vec<Value> {
+        // lsp completion item kinds
+        const KEYWORD: usize = 14;
+        const STRUCT: usize = 22;
+        const ENUM: usize = 13;
+        let builtins = SimpleType::ALL
+            .iter()
+            .map(|t| t.as_str())
+            .chain(["list"])
+            .map(|name| (name, KEYWORD, None));
+        let types = self.documents.values().flat_map(|d| d.types()).map(|t| {
+            let kind = if t.is_struct() { STRUCT } else { ENUM };
+            (t.name().get(), kind, t.doc())
+        });
+        builtins
+            .chain(types)
+            .map(|(label, kind, doc)| {
+                let mut item = json!({"label": label, "kind": kind});
+                if let Some(doc) = doc {
+                    item["documentation"] = doc.into();
+                }
+                item
+            })
+            .collect()
+    }
+
+    // diagnostics for all open documents, and empty diagnostics for closed document
+    fn publish_diagnostics(&self, closed: Option<&str>) -> Vec<Value> {
+        let mut diagnostics = self.diagnostics();
+        if let Some(uri) = closed {
+            diagnostics.insert(uri, vec![]);
+        }
+        diagnostics
+            .into_iter()
+            .map(|(uri, errors)| {
+                let errors = errors
+                    .into_iter()
+                    .map(|(position, message)| {
+                        json!({
+                            "range": self.range(uri, position),
+                            // error
+                            "severity": 1,
+                            "source": "leap",
+                            "message": message,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": {"uri": uri, "diagnostics": errors},
+                })
+            })
+            .collect()
+    }
+
+    // parse errors, unknown and duplicate types, invalid default and constant values
+    fn diagnostics(&self) -> BTreeMap<&str, Vec<(Position, String)>> {
+        let mut diagnostics: BTreeMap<&str, Vec<(Position, String)>> = self
+            .documents
+            .iter()
+            .map(|(uri, d)| {
+                let errors = d.diagnostics().map(|e| (e.0, e.1.clone())).collect();
+                (uri.as_str(), errors)
+            })
+            .collect();
+        let spec = self.spec();
+        let mut errors = vec![];
+        for (path, s) in symbols::spec_symbols(&spec) {
+            let name = s.name;
+            let defined = spec
+                .get_type_by_name(name)
+                .map(|t| (t.path(), t.name().position));
+            match s.kind {
+                SymbolKind::TypeRef if defined.is_none() => {
+                    errors.push((path, s.position, format!("Unknown type `{}`", name)))
+                }
+                SymbolKind::Type if defined != Some((path, s.position)) => errors.push((
+                    path,
+                    s.position,
+                    format!("Type `{}` is defined more than once", name),
+                )),
+                _ => {}
+            }
+        }
+        for s in spec.iter_type_refs().filter_map(|t| t.as_struct()) {
+            for p in &s.props {
+                if let Some(Err(e)) = p
+                    .default
+                    .as_ref()
+                    .map(|v| v.check_type(&p.prop_type, &spec))
+                {
+                    errors.push((&s.path, p.position, e));
+                }
+            }
+        }
+        for c in spec.iter_consts() {
+            if let Err(e) = c.value.check_type(&c.value_type, &spec) {
+                errors.push((&c.path, c.position, e));
+            }
+        }
+        for (path, position, error) in errors {
+            if let Some(file_errors) = diagnostics.get_mut(path) {
+                file_errors.push((position, error));
+            }
+        }
+        for file_errors in diagnostics.values_mut() {
+            file_errors.sort_by_key(|(p, _)| p.start);
+        }
+        diagnostics
+    }
+
+    fn line_index(&self, uri: &str) -> &LineIndex {
+        static EMPTY: OnceLock<LineIndex> = OnceLock::new();
+        self.lines
+            .get(uri)
+            .unwrap_or_else(|| EMPTY.get_or_init(|| LineIndex::new("")))
+    }
+
+    fn range(&self, uri: &str, position: Position) -> Value {
+        let index = self.line_index(uri);
+        let point = |p: usize| {
+            let (line, character) = index.to_line_character(p);
+            json!({"line": line, "character": character})
+        };
+        json!({"start": point(position.start), "end": point(position.end())})
+    }
+
+    fn text_edits(&self, uri: &str, edits: Vec<TextEdit>) -> Value {
+        Value::Array(
+            edits
+                .into_iter()
+                .map(|e| json!({"range": self.range(uri, e.position), "newText": e.text}))
+                .collect(),
+        )
+    }
+
+    fn location(&self, uri: &str, position: Position) -> Value {
+        json!({"uri": uri, "range": self.range(uri, position)})
+    }
+}
+
+fn hover(spec: &LeapSpec, symbol: &Symbol) -> Option<String> {
+    let name = symbol.name;
+    let find_prop = |owner: &Name| {
+        let props = match spec.get_type_by_name(owner.get())? {
+            LeapType::Struct(s) => &s.props,
+            LeapType::Enum(e) => &e.variants,
+        };
+        props.iter().find(|p| p.name.get() == name)
+    };
+    let (code, doc) = match symbol.kind {
+        SymbolKind::Type | SymbolKind::TypeRef => {
+            let t = spec.get_type_by_name(name)?;
+            (type_header(t), t.doc())
+        }
+        SymbolKind::Prop | SymbolKind::Variant => {
+            let p = find_prop(symbol.owner?)?;
+            (p.to_string(), p.doc.as_deref())
+        }
+        SymbolKind::VariantRef => {
+            let v = find_prop(symbol.owner?)?;
+            (format!("{}.{}", symbol.owner?, v), v.doc.as_deref())
+        }
+        SymbolKind::TypeArg => (
+            format!("{} (type argument of {})", name, symbol.owner?),
+            None,
+        ),
+        SymbolKind::Builtin => (name.to_owned(), Some("Builtin type")),
+        SymbolKind::Const => {
+            let c = spec.get_const_by_name(name)?;
+            (
+                format!(".const {}: {} = {}", c.name, c.value_type, c.value),
+                c.doc.as_deref(),
+            )
+        }
+    };
+    let mut text = format!("```leap\n{}\n```", code);
+    if let Some(doc) = doc {
+        text.push_str("\n\n");
+        text.push_str(doc);
+    }
+    Some(text)
+}
+
+fn type_header(leap_type: &LeapType) -> String {
+    let keyword = if leap_type.is_struct() {
+        ".struct"
+    } else {
+        ".enum"
+    };
+    let args = leap_type.args();
+    if args.is_empty() {
+        format!("{} {}", keyword, leap_type.name())
+    } else {
+        let args: Vec<&str> = args.iter().map(|a| a.get()).collect();
+        format!("{} {}[{}]", keyword, leap_type.name(), args.join(" "))
+    }
+}
+
+// formatting options of request
+fn format_options(params: &Value) -> FormatOptions {
+    let mut options = FormatOptions::default();
+    if let Some(tab_size) = params.pointer("/options/tabSize").and_then(as_usize) {
+        options.indent_width = tab_size;
+    }
+    if let Some(insert_spaces) = params
+        .pointer("/options/insertSpaces")
+        .and_then(|v| v.as_bool())
+    {
+        options.use_tabs = !insert_spaces;
+    }
+    options
+}
+
+fn range_to_position(index: &LineIndex, range: &Value) -> Option<Position> {
+    let point = |key| {
+        let p = range.get(key)?;
+        Some(index.to_position(as_usize(p.get("line")?)?, as_usize(p.get("character")?)?))
+    };
+    let (start, end) = (point("start")?, point("end")?);
+    Some(Position::new(start, end.checked_sub(start)?))
+}
+
+fn as_usize(value: &Value) -> Option<usize> {
+    value.as_u64().map(|v| v as usize)
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+// reads content of message with `Content-Length` header, returns None at the end of input
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let mut content = vec![0; length.unwrap_or(0)];
+    reader.read_exact(&mut content)?;
+    String::from_utf8(content)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // runs server with messages, returns messages sent by server
+    fn run(messages: &[&str]) -> Vec<Value> {
+        let mut input = vec![];
+        for m in messages {
+            write!(input, "Content-Length: {}\r\n\r\n{}", m.len(), m).unwrap();
+        }
+        let mut output = vec![];
+        Server::new().run(input.as_slice(), &mut output).unwrap();
+        let mut reader = output.as_slice();
+        let mut result = vec![];
+        while let Some(m) = read_message(&mut reader).unwrap() {
+            result.push(parse(&m));
+        }
+        result
+    }
+
+    fn parse(data: &str) -> Value {
+        serde_json::from_str(data).unwrap()
+    }
+
+    fn open(uri: &str, text: &str) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {"textDocument": {"uri": uri, "text": text}},
+        })
+        .to_string()
+    }
+
+    fn request(id: usize, method: &str, uri: &str, line: usize, character: usize) -> String {
+        format!(
+            r#"{{"jsonrpc":"2.0","id":{},"method":"{}","params":{{"textDocument":{{"uri":"{}"}},"position":{{"line":{},"character":{}}}}}}}"#,
+            id, method, uri, line, character
+        )
+    }
+
+    fn result(messages: &[Value], id: usize) -> &Value {
+        messages
+            .iter()
+            .find(|m| m.get("id").and_then(as_usize) == Some(id))
+            .and_then(|m| m.get("result"))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_server() {
+        let a = "/ user of system\n.struct user\n    id: int\n    role: role\n";
+        let b = ".enum role\n    admin: user\n    guest: unknown\n";
+        let messages = run(&[
+            r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{}}"#,
+            &open("file:///a.leap", a),
+            &open("file:///b.leap", b),
+            &request(2, "textDocument/definition", "file:///a.leap", 3, 11),
+            &request(3, "textDocument/references", "file:///a.leap", 1, 9),
+            &request(4, "textDocument/hover", "file:///b.leap", 1, 12),
+            &request(5, "textDocument/completion", "file:///b.leap", 2, 11),
+            &request(6, "textDocument/formatting", "file:///a.leap", 0, 0),
+            &request(9, "textDocument/rename", "file:///a.leap", 1, 9)
+                .replace("}}}", r#"},"newName":"account"}}"#),
+            r#"{"jsonrpc":"2.0","id":7,"method":"unknown"}"#,
+            r#"{"jsonrpc":"2.0","id":8,"method":"shutdown"}"#,
+            r#"{"jsonrpc":"2.0","method":"exit"}"#,
+            // not processed after `exit`
+            r#"{"jsonrpc":"2.0","id":10,"method":"shutdown"}"#,
+        ]);
+        assert!(result(&messages, 1)
+            .pointer("/capabilities/hoverProvider")
+            .is_some());
+        // diagnostics after opening of the second document
+        let diagnostics: Vec<&Value> = messages
+            .iter()
+            .filter(|m| {
+                m.get("method").and_then(|m| m.as_str()) == Some("textDocument/publishDiagnostics")
+            })
+            .map(|m| m.get("params").unwrap())
+            .collect();
+        assert_eq!(
+            diagnostics[1..],
+            [
+                &parse(r#"{"uri":"file:///a.leap","diagnostics":[]}"#),
+                &parse(
+                    r#"{"uri":"file:///b.leap","diagnostics":[{"range":{"start":{"line":2,"character":11},"end":{"line":2,"character":18}},"severity":1,"source":"leap","message":"Unknown type `unknown`"}]}"#
+                )
+            ]
+        );
+        assert_eq!(
+            result(&messages, 2),
+            &parse(
+                r#"{"uri":"file:///b.leap","range":{"start":{"line":0,"character":6},"end":{"line":0,"character":10}}}"#
+            )
+        );
+        assert_eq!(
+            result(&messages, 3),
+            &parse(
+                r#"[
+                    {"uri":"file:///a.leap","range":{"start":{"line":1,"character":8},"end":{"line":1,"character":12}}},
+                    {"uri":"file:///b.leap","range":{"start":{"line":1,"character":11},"end":{"line":1,"character":15}}}
+                ]"#
+            )
+        );
+        assert_eq!(
+            result(&messages, 4)
+                .pointer("/contents/value")
+                .unwrap()
+                .as_str(),
+            Some("```leap\n.struct user\n```\n\nuser of system")
+        );
+        let labels: Vec<&str> = result(&messages, 5)
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|i| i.get("label").and_then(|l| l.as_str()))
+            .collect();
+        assert!(labels.contains(&"user"));
+        assert!(labels.contains(&"role"));
+        assert!(labels.contains(&"uuid"));
+        assert!(labels.contains(&"list"));
+        assert_eq!(
+            result(&messages, 6),
+            &parse(
+                r#"[{"range":{"start":{"line":0,"character":0},"end":{"line":1,"character":0}},"newText":"/-- user of system\n"}]"#
+            )
+        );
+        let changes = result(&messages, 9).get("changes").unwrap();
+        assert_eq!(
+            changes.get("file:///b.leap").unwrap(),
+            &parse(
+                r#"[{"range":{"start":{"line":1,"character":11},"end":{"line":1,"character":15}},"newText":"account"}]"#
+            )
+        );
+        assert_eq!(
+            messages
+                .iter()
+                .find(|m| m.get("id").and_then(as_usize) == Some(7))
+                .and_then(|m| m.pointer("/error/code")),
+            Some(&json!(METHOD_NOT_FOUND))
+        );
+        assert_eq!(result(&messages, 8), &Value::Null);
+        assert_eq!(
+            messages.last().unwrap().get("id").and_then(as_usize),
+            Some(8)
+        );
+    }
+
+    #[test]
+    fn test_incremental_change() {
+        let change = r#"{"jsonrpc":"2.0","method":"textDocument/didChange","params":{"textDocument":{"uri":"file:///a.leap"},"contentChanges":[{"range":{"start":{"line":1,"character":7},"end":{"line":1,"character":10}},"text":"str"}]}}"#;
+        let messages = run(&[&open("file:///a.leap", ".struct a\n    x: boo\n"), change]);
+        let errors: Vec<&Value> = messages
+            .iter()
+            .map(|m| m.pointer("/params/diagnostics").unwrap())
+            .collect();
+        assert_eq!(errors[0].as_array().unwrap().len(), 1);
+        assert_eq!(errors[1].as_array().unwrap().len(), 0);
+    }
+}
@@ -1,98 +1,56 @@
-use std::{
-    iter::{Enumerate, Peekable},
-    str::Chars,
-};
-
 use crate::{
     leaptypes::{Comment, CommentType},
+    parser::cst::{self, TokenKind},
     parser::position::Position,
 };
 
-// comments parser is separte from types parser for simplicity
+// comments parser is separte from types parser for simplicity, both use tokens of the same lexer
 pub fn parse(data: &str) -> Vec<Comment> {
     let mut comments = vec![];
-    let mut chars = data.chars().enumerate().peekable();
-    // let v: String = chars;
-    while let Some((i, v)) = chars.next() {
-        if v == '/' {
-            // ::Line comment
-            let (comment, length) = parse_comment(&mut chars);
-            comments.push(Comment {
-                comment,
-                comment_type: CommentType::Line,
-                position: Position::new(i, length),
-            });
-        } else if v.is_whitespace() {
-            let mut v = v;
-            while let Some((_, next_v)) = chars.peek() {
-                if v == '\n' {
-                    break;
-                };
-                if !next_v.is_whitespace() {
-                    // not an empty line
-                    break;
+    let mut line_start = 0;
+    // line has tokens other than whitespace
+    let mut is_empty_line = true;
+    for t in cst::tokenize(data) {
+        match t.kind {
+            TokenKind::Whitespace => {}
+            TokenKind::Newline => {
+                if is_empty_line {
+                    // ::Separator
+                    comments.push(Comment {
+                        comment: String::new(),
+                        comment_type: CommentType::Separator,
+                        position: Position::new(line_start, 0),
+                    });
                 }
-                v = if let Some((_, new_v)) = chars.next() {
-                    new_v
-                } else {
-                    break;
-                };
+                line_start = t.position.end();
+                is_empty_line = true;
             }
-            if v == '\n' {
-                // found empty line
-                // ::Separator
+            TokenKind::Comment => {
                 comments.push(Comment {
-                    comment: String::new(),
-                    comment_type: CommentType::Separator,
-                    position: Position::new(i, 0),
+                    comment: comment_text(&t.text),
+                    comment_type: if is_empty_line {
+                        CommentType::Line
+                    } else {
+                        CommentType::Trail
+                    },
+                    position: t.position,
                 });
+                is_empty_line = false;
             }
-        } else if !v.is_whitespace() {
-            // ::Trail comment
-            // skip up to `/` or new line
-            for (i, v) in &mut chars {
-                match v {
-                    '/' => {
-                        let (comment, length) = parse_comment(&mut chars);
-                        comments.push(Comment {
-                            comment,
-                            comment_type: CommentType::Trail,
-                            position: Position::new(i, length),
-                        });
-                        break;
-                    }
-                    '\n' => break,
-                    _ => {}
-                };
-            }
+            _ => is_empty_line = false,
         }
     }
     comments
 }
 
-fn parse_comment(chars: &mut Peekable<Enumerate<Chars>>) -> (String, usize) {
-    let mut comment_mark_len = 1;
-    while chars.peek().map(|(_, c)| *c == '-').unwrap_or(false) {
-        chars.next();
-        comment_mark_len += 1;
-        if comment_mark_len == 3 {
-            // prefix is `/--`
-            break;
-        }
-    }
-    let comment = consume_up_to_line_end(chars);
-    (comment.trim().to_owned(), comment_mark_len + comment.len())
-}
-
-fn consume_up_to_line_end(chars: &mut impl Iterator<Item = (usize, char)>) -> String {
-    let mut s = vec![];
-    for (_, v) in chars {
-        if v == '\n' {
-            break;
-        }
-        s.push(v);
-    }
-    s.into_iter().collect()
+// text without `/`, `/-` or `/--` prefix
+fn comment_text(text: &str) -> String {
+    let text = &text[1..];
+    let text = text
+        .strip_prefix("--")
+        .or_else(|| text.strip_prefix('-'))
+        .unwrap_or(text);
+    text.trim().to_owned()
 }
 
 #[cfg(test)]
@@ -147,5 +105,9 @@ mod tests {
         assert_eq!(comments.len(), 4);
         let comments = parse("/-- text");
         assert_eq!(comments[0].comment, "text");
+        let comments = parse("a: str = \"a/\\\"b/\" / text");
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].position.start, 18);
+        assert_eq!(comments[0].comment, "text");
     }
 }
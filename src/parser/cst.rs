@@ -0,0 +1,584 @@
+// Lossless concrete syntax tree, keeps every character of the source including comments and
+// whitespace, so `parse(data).text() == data`
+use std::{fmt, iter::Peekable, str::Chars};
+
+use super::position::Position;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    // spaces and tabs
+    Whitespace,
+    Newline,
+    // `/-- text` up to the end of line
+    Comment,
+    Word,
+    // ".struct"
+    Struct,
+    // ".enum"
+    Enum,
+    // ".const"
+    Const,
+    // "["
+    BracketLeft,
+    // "]"
+    BracketRight,
+    // ":"
+    Colon,
+    // "="
+    Equals,
+    // "\"text\"", including quotes
+    Str,
+    // "@name value"
+    Attribute,
+    // characters ignored by the parser
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeKind {
+    Root,
+    Struct,
+    Enum,
+    Const,
+    Prop,
+    Variant,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxToken {
+    pub kind: TokenKind,
+    pub text: String,
+    pub position: Position,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxNode {
+    pub kind: NodeKind,
+    pub position: Position,
+    pub children: Vec<SyntaxElement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxToken {
+    pub fn is_trivia(&self) -> bool {
+        matches!(
+            self.kind,
+            TokenKind::Whitespace | TokenKind::Newline | TokenKind::Comment | TokenKind::Unknown
+        )
+    }
+}
+
+impl SyntaxElement {
+    pub fn position(&self) -> &Position {
+        match self {
+            Self::Node(n) => &n.position,
+            Self::Token(t) => &t.position,
+        }
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for t in self.tokens() {
+            write!(f, "{}", t.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl SyntaxNode {
+    pub fn text(&self) -> String {
+        self.to_string()
+    }
+
+    // all tokens of the node and its children in source order
+    pub fn tokens(&self) -> Vec<&SyntaxToken> {
+        let mut tokens = vec![];
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a SyntaxToken>) {
+        for c in &self.children {
+            match c {
+                SyntaxElement::Node(n) => n.collect_tokens(tokens),
+                SyntaxElement::Token(t) => tokens.push(t),
+            }
+        }
+    }
+
+    // direct child nodes
+    pub fn nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|c| match c {
+            SyntaxElement::Node(n) => Some(n),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    // token which contains character at `offset`
+    pub fn token_at(&self, offset: usize) -> Option<&SyntaxToken> {
+        self.tokens()
+            .into_iter()
+            .find(|t| t.position.start <= offset && offset < t.position.end())
+    }
+}
+
+// parses data to syntax tree, it doesn't fail on invalid input: definition or prop ends at the
+// first token which doesn't fit into it, tokens outside of definitions are children of the root
+pub fn parse(data: &str) -> SyntaxNode {
+    let tokens = tokenize(data);
+    let mut parser = CstParser {
+        tokens: tokens.iter().filter(|t| !t.is_trivia()).collect(),
+        cursor: 0,
+    };
+    let root = Span {
+        kind: NodeKind::Root,
+        position: Position::new(0, data.chars().count()),
+        children: parser.definitions(),
+    };
+    build(root, &mut tokens.into_iter().peekable())
+}
+
+// node without tokens
+struct Span {
+    kind: NodeKind,
+    position: Position,
+    children: Vec<Span>,
+}
+
+// parser over not trivia tokens, it only finds where nodes start and end
+struct CstParser<'a> {
+    tokens: Vec<&'a SyntaxToken>,
+    cursor: usize,
+}
+
+impl CstParser<'_> {
+    fn definitions(&mut self) -> Vec<Span> {
+        let mut definitions = vec![];
+        while self.cursor < self.tokens.len() {
+            match self.kind_after_attributes() {
+                Some(TokenKind::Struct | TokenKind::Enum | TokenKind::Const) => {
+                    definitions.push(self.definition())
+                }
+                _ => self.cursor += 1,
+            }
+        }
+        definitions
+    }
+
+    fn definition(&mut self) -> Span {
+        let start = self.cursor;
+        while self.eat(TokenKind::Attribute) {}
+        let kind = match self.kind() {
+            Some(TokenKind::Struct) => NodeKind::Struct,
+            Some(TokenKind::Enum) => NodeKind::Enum,
+            _ => NodeKind::Const,
+        };
+        self.cursor += 1;
+        self.eat(TokenKind::Word);
+        let mut children = vec![];
+        if kind == NodeKind::Const {
+            if self.eat(TokenKind::Colon) {
+                self.value_type();
+                if self.eat(TokenKind::Equals) {
+                    self.value();
+                }
+            }
+        } else {
+            if self.eat(TokenKind::BracketLeft) {
+                while self.eat(TokenKind::Word) {}
+                self.eat(TokenKind::BracketRight);
+            }
+            loop {
+                match self.kind_after_attributes() {
+                    None | Some(TokenKind::Struct | TokenKind::Enum | TokenKind::Const) => break,
+                    Some(TokenKind::Word) => children.push(self.prop(kind)),
+                    // unexpected token is kept in the definition
+                    _ => self.cursor += 1,
+                }
+            }
+        }
+        Span {
+            kind,
+            position: self.position_from(start),
+            children,
+        }
+    }
+
+    fn prop(&mut self, owner: NodeKind) -> Span {
+        let start = self.cursor;
+        while self.eat(TokenKind::Attribute) {}
+        self.eat(TokenKind::Word);
+        let kind = if owner == NodeKind::Struct {
+            if self.eat(TokenKind::Colon) {
+                self.value_type();
+                if self.eat(TokenKind::Equals) {
+                    self.value();
+                }
+            }
+            NodeKind::Prop
+        } else {
+            // variant named by its type, eg. `page[int]`
+            if self.kind() == Some(TokenKind::BracketLeft) {
+                self.type_args();
+            } else if self.eat(TokenKind::Colon) {
+                self.value_type();
+            }
+            NodeKind::Variant
+        };
+        Span {
+            kind,
+            position: self.position_from(start),
+            children: vec![],
+        }
+    }
+
+    fn value_type(&mut self) {
+        if self.eat(TokenKind::Word) && self.kind() == Some(TokenKind::BracketLeft) {
+            self.type_args();
+        }
+    }
+
+    fn type_args(&mut self) {
+        self.eat(TokenKind::BracketLeft);
+        while self.kind() == Some(TokenKind::Word) {
+            self.value_type();
+        }
+        self.eat(TokenKind::BracketRight);
+    }
+
+    fn value(&mut self) {
+        if !self.eat(TokenKind::Word)
+            && !self.eat(TokenKind::Str)
+            && self.eat(TokenKind::BracketLeft)
+        {
+            self.eat(TokenKind::BracketRight);
+        }
+    }
+
+    fn kind(&self) -> Option<TokenKind> {
+        self.tokens.get(self.cursor).map(|t| t.kind)
+    }
+
+    // kind of the first token after attributes
+    fn kind_after_attributes(&self) -> Option<TokenKind> {
+        self.tokens[self.cursor..]
+            .iter()
+            .map(|t| t.kind)
+            .find(|k| *k != TokenKind::Attribute)
+    }
+
+    fn eat(&mut self, kind: TokenKind) -> bool {
+        let is_eaten = self.kind() == Some(kind);
+        if is_eaten {
+            self.cursor += 1;
+        }
+        is_eaten
+    }
+
+    // from start of token at index up to end of the last consumed token
+    fn position_from(&self, index: usize) -> Position {
+        let start = self.tokens[index].position.start;
+        Position::new(start, self.tokens[self.cursor - 1].position.end() - start)
+    }
+}
+
+// node with tokens of the span, tokens before, between and after child spans are children of
+// the node
+fn build(span: Span, tokens: &mut Peekable<impl Iterator<Item = SyntaxToken>>) -> SyntaxNode {
+    let mut node = SyntaxNode {
+        kind: span.kind,
+        position: span.position,
+        children: vec![],
+    };
+    for child in span.children {
+        take_tokens_before(tokens, child.position.start, &mut node.children);
+        node.children
+            .push(SyntaxElement::Node(build(child, tokens)));
+    }
+    take_tokens_before(tokens, span.position.end(), &mut node.children);
+    node
+}
+
+fn take_tokens_before(
+    tokens: &mut Peekable<impl Iterator<Item = SyntaxToken>>,
+    end: usize,
+    children: &mut Vec<SyntaxElement>,
+) {
+    while let Some(t) = tokens.next_if(|t| t.position.start < end) {
+        children.push(SyntaxElement::Token(t));
+    }
+}
+
+// splits data to tokens, concatenated tokens text is equal to data, it is the only lexer of
+// leap, parser and comments parser use its tokens
+pub fn tokenize(data: &str) -> Vec<SyntaxToken> {
+    let mut tokens = vec![];
+    let mut chars = data.chars().peekable();
+    let mut start = 0;
+    while let Some(v) = chars.next() {
+        let mut text = v.to_string();
+        let kind = match v {
+            '\n' => TokenKind::Newline,
+            '[' => TokenKind::BracketLeft,
+            ']' => TokenKind::BracketRight,
+            ':' => TokenKind::Colon,
+            '=' => TokenKind::Equals,
+            '/' => {
+                take_while(&mut chars, &mut text, |c| c != '\n');
+                TokenKind::Comment
+            }
+            '@' => {
+                // `/` starts a comment only right after `@` or after whitespace
+                let mut previous = ' ';
+                while let Some(c) =
+                    chars.next_if(|c| *c != '\n' && (*c != '/' || !previous.is_whitespace()))
+                {
+                    text.push(c);
+                    previous = c;
+                }
+                // trailing whitespace is a separate token
+                let trimmed_length = text.trim_end().len();
+                let trailing = text.split_off(trimmed_length);
+                if !trailing.is_empty() {
+                    let length = text.chars().count();
+                    tokens.push(SyntaxToken {
+                        kind: TokenKind::Attribute,
+                        position: Position::new(start, length),
+                        text,
+                    });
+                    start += length;
+                    text = trailing;
+                    TokenKind::Whitespace
+                } else {
+                    TokenKind::Attribute
+                }
+            }
+            '"' => {
+                let mut is_escaped = false;
+                while let Some(c) = chars.next_if(|c| *c != '\n') {
+                    text.push(c);
+                    if is_escaped {
+                        is_escaped = false;
+                    } else if c == '\\' {
+                        is_escaped = true;
+                    } else if c == '"' {
+                        break;
+                    }
+                }
+                TokenKind::Str
+            }
+            _ if is_word_char(v) => {
+                take_while(&mut chars, &mut text, is_word_char);
+                match text.as_str() {
+                    ".struct" => TokenKind::Struct,
+                    ".enum" => TokenKind::Enum,
+                    ".const" => TokenKind::Const,
+                    _ => TokenKind::Word,
+                }
+            }
+            _ if v.is_whitespace() => {
+                take_while(&mut chars, &mut text, |c| c != '\n' && c.is_whitespace());
+                TokenKind::Whitespace
+            }
+            _ => TokenKind::Unknown,
+        };
+        let length = text.chars().count();
+        tokens.push(SyntaxToken {
+            kind,
+            text,
+            position: Position::new(start, length),
+        });
+        start += length;
+    }
+    tokens
+}
+
+// splits data to top level blocks covering all data, every block (except the first one, which
+// always starts at 0) starts with a line of `.struct`, `.enum` or `.const` definition, including
+// attributes and comments placed right before it
+pub fn split_blocks(data: &str) -> Vec<Position> {
+    // start of line and kind of first not whitespace token on the line
+    let mut lines: Vec<(usize, Option<TokenKind>)> = vec![(0, None)];
+    for t in tokenize(data) {
+        match t.kind {
+            TokenKind::Newline => lines.push((t.position.end(), None)),
+            TokenKind::Whitespace => {}
+            kind => {
+                let line = lines.last_mut().unwrap();
+                line.1 = line.1.or(Some(kind));
+            }
+        }
+    }
+    let mut starts = vec![0];
+    for (i, (_, kind)) in lines.iter().enumerate() {
+        if matches!(
+            kind,
+            Some(TokenKind::Struct | TokenKind::Enum | TokenKind::Const)
+        ) {
+            let mut first = i;
+            while first > 0
+                && matches!(
+                    lines[first - 1].1,
+                    Some(TokenKind::Attribute | TokenKind::Comment)
+                )
+            {
+                first -= 1;
+            }
+            if lines[first].0 > *starts.last().unwrap() {
+                starts.push(lines[first].0);
+            }
+        }
+    }
+    let length = data.chars().count();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(length);
+            Position::new(*start, end - start)
+        })
+        .collect()
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '.'
+}
+
+fn take_while(chars: &mut Peekable<Chars>, text: &mut String, f: impl Fn(char) -> bool) {
+    while let Some(c) = chars.next_if(|c| f(*c)) {
+        text.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let tokens = tokenize(".struct a  / text\n  @min 0 \n  b: list[int] = \"x\"");
+        let kinds: Vec<_> = tokens.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Struct,
+                TokenKind::Whitespace,
+                TokenKind::Word,
+                TokenKind::Whitespace,
+                TokenKind::Comment,
+                TokenKind::Newline,
+                TokenKind::Whitespace,
+                TokenKind::Attribute,
+                TokenKind::Whitespace,
+                TokenKind::Newline,
+                TokenKind::Whitespace,
+                TokenKind::Word,
+                TokenKind::Colon,
+                TokenKind::Whitespace,
+                TokenKind::Word,
+                TokenKind::BracketLeft,
+                TokenKind::Word,
+                TokenKind::BracketRight,
+                TokenKind::Whitespace,
+                TokenKind::Equals,
+                TokenKind::Whitespace,
+                TokenKind::Str,
+            ]
+        );
+        assert_eq!(tokens[7].text, "@min 0");
+        assert_eq!(tokens[7].position, Position::new(20, 6));
+        assert_eq!(tokens[21].text, "\"x\"");
+        let tokens = tokenize("@format http://x / text");
+        assert_eq!(tokens[0].text, "@format http://x");
+        assert_eq!(tokens[2].kind, TokenKind::Comment);
+    }
+
+    #[test]
+    fn test_split_blocks() {
+        let data = "/ header\n\n.struct a\n    x: int\n/ doc\n@since 1\n.enum b\n    a\n\n/ not doc\n\n.const c: int = 1\n";
+        let blocks = split_blocks(data);
+        let texts: Vec<String> = blocks
+            .iter()
+            .map(|p| data.chars().skip(p.start).take(p.length).collect())
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                "/ header\n\n",
+                ".struct a\n    x: int\n",
+                "/ doc\n@since 1\n.enum b\n    a\n\n/ not doc\n\n",
+                ".const c: int = 1\n",
+            ]
+        );
+        assert_eq!(split_blocks(""), vec![Position::new(0, 0)]);
+    }
+
+    #[test]
+    fn test_lossless() {
+        let data = "
+        / text1 text
+        @since 2
+        .struct some-my-struct[t] / text4
+
+            v1: list[t] /text8
+            @min  0
+            v2: int = 3  ,
+        / text9
+        .enum value-enum
+            val1
+            aaa: имя
+        .const c: str = \"a/b\"   \r\n";
+        let root = parse(data);
+        assert_eq!(root.text(), data);
+        let nodes: Vec<_> = root.nodes().collect();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0].kind, NodeKind::Struct);
+        assert!(nodes[0].text().starts_with("@since 2"));
+        assert!(nodes[0].text().ends_with("v2: int = 3"));
+        let props: Vec<_> = nodes[0].nodes().collect();
+        assert_eq!(props.len(), 2);
+        assert_eq!(props[1].kind, NodeKind::Prop);
+        assert_eq!(props[1].text(), "@min  0\n            v2: int = 3");
+        assert_eq!(nodes[1].kind, NodeKind::Enum);
+        assert_eq!(nodes[1].nodes().nth(1).unwrap().text(), "aaa: имя");
+        assert_eq!(nodes[2].kind, NodeKind::Const);
+        assert_eq!(nodes[2].text(), ".const c: str = \"a/b\"");
+        let t = root.token_at(30).unwrap();
+        assert_eq!(t.kind, TokenKind::Attribute);
+        assert_eq!(parse(".struct").nodes().next().unwrap().text(), ".struct");
+    }
+
+    #[test]
+    fn test_broken_input() {
+        let data = "
+        stray
+        .struct s
+            a: list[int = ]
+            : ,
+            @min 0
+            b: str = \"text
+        @since 2
+        .enum e
+            x: s ]
+        .const c: =";
+        let root = parse(data);
+        assert_eq!(root.text(), data);
+        let nodes: Vec<_> = root.nodes().collect();
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(nodes[0].kind, NodeKind::Struct);
+        let props: Vec<_> = nodes[0].nodes().collect();
+        assert_eq!(props.len(), 2);
+        assert_eq!(props[0].text(), "a: list[int =");
+        assert_eq!(props[1].text(), "@min 0\n            b: str = \"text");
+        assert!(nodes[1].text().starts_with("@since 2"));
+        assert_eq!(nodes[1].nodes().next().unwrap().text(), "x: s");
+        assert_eq!(nodes[2].text(), ".const c: =");
+        assert_eq!(parse("").text(), "");
+    }
+}
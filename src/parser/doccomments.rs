@@ -0,0 +1,129 @@
+use crate::leaptypes::{CommentType, LeapDefinition, LeapType, Prop};
+
+use super::commentsparser;
+
+// item which can have doc comment
+struct Anchor {
+    // start of the first line of item, including attributes
+    start: usize,
+    // line where trail comment of item can be placed
+    line: usize,
+}
+
+// doc comment is made from line comments placed right before item (without empty lines in between),
+// and trail comment placed on the same line with item
+pub fn attach(data: &str, definitions: &mut [LeapDefinition]) {
+    let comments = commentsparser::parse(data);
+    if comments.is_empty() {
+        return;
+    }
+    let line_starts = line_starts(data);
+    let anchors = anchors(definitions, &line_starts);
+    let mut docs: Vec<Vec<&str>> = vec![vec![]; anchors.len()];
+    let mut comments_iter = comments.iter().peekable();
+    let mut previous_line = None;
+    for (i, anchor) in anchors.iter().enumerate() {
+        let mut lines = vec![];
+        while let Some(c) = comments_iter.next_if(|c| c.position.start < anchor.start) {
+            // comments placed up to the last line of previous item can't be attached
+            let is_after_previous =
+                previous_line.is_none_or(|l| l < line_of(&line_starts, c.position.start));
+            match c.comment_type {
+                CommentType::Line if is_after_previous => lines.push(c.comment.as_str()),
+                _ => lines.clear(),
+            }
+        }
+        docs[i] = lines;
+        previous_line = Some(anchor.line);
+    }
+    for c in comments
+        .iter()
+        .filter(|c| c.comment_type == CommentType::Trail)
+    {
+        let line = line_of(&line_starts, c.position.start);
+        if let Some(i) = anchors.iter().rposition(|a| a.line == line) {
+            docs[i].push(c.comment.as_str());
+        }
+    }
+    for (target, lines) in doc_targets(definitions).into_iter().zip(docs) {
+        if !lines.is_empty() {
+            *target = Some(lines.join("\n"));
+        }
+    }
+}
+
+fn line_starts(data: &str) -> Vec<usize> {
+    let mut starts = vec![0];
+    for (i, c) in data.chars().enumerate() {
+        if c == '\n' {
+            starts.push(i + 1);
+        }
+    }
+    starts
+}
+
+fn line_of(line_starts: &[usize], position: usize) -> usize {
+    match line_starts.binary_search(&position) {
+        Ok(line) => line,
+        Err(line) => line - 1,
+    }
+}
+
+fn prop_anchor(prop: &Prop, line_starts: &[usize]) -> Anchor {
+    Anchor {
+        start: prop
+            .attributes
+            .first()
+            .map_or(prop.position.start, |a| a.position.start),
+        line: line_of(line_starts, prop.position.end().saturating_sub(1)),
+    }
+}
+
+// anchors in same order as `doc_targets`
+fn anchors(definitions: &[LeapDefinition], line_starts: &[usize]) -> Vec<Anchor> {
+    let mut anchors = vec![];
+    for definition in definitions {
+        let start = definition
+            .attributes()
+            .first()
+            .map_or(definition.position().start, |a| a.position.start);
+        match definition {
+            LeapDefinition::Type(t) => {
+                anchors.push(Anchor {
+                    start,
+                    line: line_of(line_starts, t.name().position.start),
+                });
+                let props = match t {
+                    LeapType::Struct(s) => &s.props,
+                    LeapType::Enum(e) => &e.variants,
+                };
+                for p in props {
+                    anchors.push(prop_anchor(p, line_starts));
+                }
+            }
+            LeapDefinition::Const(c) => anchors.push(Anchor {
+                start,
+                line: line_of(line_starts, c.position.end().saturating_sub(1)),
+            }),
+        }
+    }
+    anchors
+}
+
+fn doc_targets(definitions: &mut [LeapDefinition]) -> Vec<&mut Option<String>> {
+    let mut targets = vec![];
+    for definition in definitions {
+        match definition {
+            LeapDefinition::Type(LeapType::Struct(s)) => {
+                targets.push(&mut s.doc);
+                targets.extend(s.props.iter_mut().map(|p| &mut p.doc));
+            }
+            LeapDefinition::Type(LeapType::Enum(e)) => {
+                targets.push(&mut e.doc);
+                targets.extend(e.variants.iter_mut().map(|v| &mut v.doc));
+            }
+            LeapDefinition::Const(c) => targets.push(&mut c.doc),
+        }
+    }
+    targets
+}
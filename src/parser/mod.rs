@@ -1,6 +1,9 @@
 // todo: fix name typesparser?
+#[allow(clippy::module_inception)]
 pub mod parser;
 pub mod commentsparser;
+pub mod cst;
+mod doccomments;
 mod parsetree;
 pub mod patherror;
 pub mod itemposition;
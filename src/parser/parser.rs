@@ -1,3 +1,5 @@
+use super::doccomments;
+use super::position::Position;
 use super::propsimple::PropSimple;
 use super::proptypesimple::PropTypeSimple;
 use super::token::Token;
@@ -6,32 +8,45 @@ use super::{
     itemposition::ItemPosition, parsetree::ParseTree, patherror::PathError,
     treevariant::TreeVariant,
 };
-use crate::leaptypes::{LeapEnum, LeapSpec, LeapStruct, LeapType, Name, Prop};
+use crate::leaptypes::{
+    is_builtin_name, Attribute, LeapConst, LeapDefinition, LeapEnum, LeapSpec, LeapStruct,
+    LeapType, Literal, Name, Prop, ValueType,
+};
 use std::fs;
 
 /*
 
     Full BNF
 
-    START               := STRUCT_DEF | ENUM_DEF
+    START               := ATTRIBUTES STRUCT_DEF | ATTRIBUTES ENUM_DEF | ATTRIBUTES CONST_DEF
+
+    ATTRIBUTES          := ATTRIBUTE ATTRIBUTES | e
 
     STRUCT_DEF          := STRUCT NAME T_ARGS_DEF PROPS_DEF
     T_ARGS_DEF          := [ T_ARGS ] | e
     T_ARGS              := NAME | NAME T_ARGS
-    PROPS_DEF           := PROP PROPS_DEF | e
-    PROP                := NAME COLON PTYPE
+    PROPS_DEF           := ATTRIBUTES PROP PROPS_DEF | e
+    PROP                := NAME COLON PTYPE DEFAULT
+    DEFAULT             := EQUALS VALUE | e
 
     ENUM_DEF            := ENUM NAME T_ARGS_DEF VARIANTS_DEF
-    VARIANTS_DEF        := VARIANT VARIANTS_DEF | e
+    VARIANTS_DEF        := ATTRIBUTES VARIANT VARIANTS_DEF | e
     VARIANT             := PROP | PTYPE
 
+    CONST_DEF           := CONST NAME COLON PTYPE EQUALS VALUE
+
     PTYPE               := NAME | NAME PT_ARGS_BLOCK
     PT_ARGS_BLOCK       := [ PT_ARGS ]
     PT_ARGS             := PTYPE | PTYPE PT_ARGS
 
+    VALUE               := word | string | [ ]
+
     NAME                := word
+    ATTRIBUTE           := "@" word | "@" word text
+    EQUALS              := "="
     STRUCT              := ".struct"
     ENUM                := ".enum"
+    CONST               := ".const"
 
 */
 
@@ -52,12 +67,12 @@ impl Parser {
                 Err(e) => Err(PathError::new(format!("{}", e), path.to_owned(), 0)),
             }
         }
-        fn parse((path, data): (&str, String)) -> Result<Vec<(&str, LeapType)>, PathError> {
-            Parser::parse(&data)
+        fn parse((path, data): (&str, String)) -> Result<Vec<(&str, LeapDefinition)>, PathError> {
+            Parser::parse_definitions(&data)
                 .map_err(|e| PathError::new(e.1, path.to_owned(), e.0.start))
-                .map(|t| t.into_iter().map(|t| (path, t)).collect())
+                .map(|d| d.into_iter().map(|d| (path, d)).collect())
         }
-        let types = paths
+        let definitions = paths
             .map(read_to_string)
             .collect::<Result<Vec<_>, _>>()?
             .into_iter()
@@ -65,16 +80,30 @@ impl Parser {
             .collect::<Result<Vec<Vec<_>>, _>>()?
             .into_iter()
             .flatten()
-            .map(|(path, mut leap_type)| {
-                leap_type.set_path(path.to_owned());
-                leap_type
+            .map(|(path, mut definition)| {
+                definition.set_path(path.to_owned());
+                definition
             })
             .collect();
-        Ok(LeapSpec::new(types))
+        let spec = LeapSpec::from_definitions(definitions);
+        spec.check_defaults()?;
+        spec.check_consts()?;
+        Ok(spec)
     }
 
+    // parses only types, constants are skipped, use `parse_definitions` to get both
     pub fn parse(data: &str) -> Result<Vec<LeapType>, ItemPosition<String>> {
-        let stream = TokenStream::new(&data);
+        Ok(Self::parse_definitions(data)?
+            .into_iter()
+            .filter_map(|d| match d {
+                LeapDefinition::Type(t) => Some(t),
+                LeapDefinition::Const(_) => None,
+            })
+            .collect())
+    }
+
+    pub fn parse_definitions(data: &str) -> Result<Vec<LeapDefinition>, ItemPosition<String>> {
+        let stream = TokenStream::new(data);
         let mut parser = Parser { stream };
         let mut trees = vec![];
         while parser.stream.get().1 != Token::End {
@@ -86,24 +115,95 @@ impl Parser {
             for t in &mut trees {
                 t.calc_length();
             }
-            trees
+            let mut definitions = trees
                 .into_iter()
-                .map(|t| Self::tree_to_leaptype(&t))
-                .collect::<Result<Vec<LeapType>, ItemPosition<String>>>()
+                .map(|t| Self::tree_to_definition(&t))
+                .collect::<Result<Vec<LeapDefinition>, ItemPosition<String>>>()?;
+            Self::check_values(&definitions)?;
+            doccomments::attach(data, &mut definitions);
+            Ok(definitions)
         }
     }
 
+    // default and constant values, values of enums defined in other files are checked when spec
+    // is complete (`LeapSpec::check_defaults` and `LeapSpec::check_consts`)
+    fn check_values(definitions: &[LeapDefinition]) -> Result<(), ItemPosition<String>> {
+        let enums = LeapSpec::new(
+            definitions
+                .iter()
+                .filter_map(|d| d.as_type().filter(|t| t.is_enum()).cloned())
+                .collect(),
+        );
+        let check = |value: &Literal, value_type: &ValueType, position: Position| {
+            if let (Literal::EnumVariant { .. }, ValueType::LeapType { name, .. }) =
+                (value, value_type)
+            {
+                if enums.get_type_by_name(name.get()).is_none() {
+                    return Ok(());
+                }
+            }
+            value
+                .check_type(value_type, &enums)
+                .map_err(|e| ItemPosition(position, e))
+        };
+        for definition in definitions {
+            match definition {
+                LeapDefinition::Type(LeapType::Struct(s)) => {
+                    for p in &s.props {
+                        if let Some(default) = &p.default {
+                            check(default, &p.prop_type, p.position)?;
+                        }
+                    }
+                }
+                LeapDefinition::Type(LeapType::Enum(_)) => {}
+                LeapDefinition::Const(c) => check(&c.value, &c.value_type, c.position)?,
+            }
+        }
+        Ok(())
+    }
+
     fn parse_start(&mut self) -> Result<ParseTree, ItemPosition<String>> {
         let mut tree = ParseTree::new(TreeVariant::Start, self.stream.get().0);
+        tree.nodes.push(self.parse_attributes()?);
         let node = match self.stream.get() {
             ItemPosition(.., Token::Struct) => self.parse_struct_def()?,
             ItemPosition(.., Token::Enum) => self.parse_enum_def()?,
-            p => return Err(p.replace("Expecting `.enum` or `.struct`".to_owned())),
+            ItemPosition(.., Token::Const) => self.parse_const_def()?,
+            p => return Err(p.replace("Expecting `.enum`, `.struct` or `.const`".to_owned())),
         };
         tree.nodes.push(node);
         Ok(tree)
     }
 
+    fn parse_attributes(&mut self) -> Result<ParseTree, ItemPosition<String>> {
+        let mut tree = ParseTree::new(TreeVariant::Attributes, self.stream.get().0);
+        if let Token::Attribute(_) = self.stream.get().1 {
+            tree.nodes.push(self.parse_attribute()?);
+            tree.nodes.push(self.parse_attributes()?);
+        }
+        Ok(tree)
+    }
+
+    fn parse_attribute(&mut self) -> Result<ParseTree, ItemPosition<String>> {
+        // attribute after an item on the same line would be attached to the next item
+        if !self.stream.starts_line() {
+            return Err(self
+                .stream
+                .get()
+                .replace("Attribute should be on its own line".to_owned()));
+        }
+        match self.stream.consume() {
+            ItemPosition(p, Token::Attribute(a)) => {
+                if a.starts_with(|c: char| c.is_alphanumeric()) {
+                    Ok(ParseTree::new(TreeVariant::Attribute(a.clone()), *p))
+                } else {
+                    Err(ItemPosition(*p, "Expecting attribute name".to_owned()))
+                }
+            }
+            p => Err(p.replace("Expecting attribute".to_owned())),
+        }
+    }
+
     fn parse_struct_def(&mut self) -> Result<ParseTree, ItemPosition<String>> {
         let mut tree = ParseTree::new(TreeVariant::StructDef, self.stream.get().0);
         if self.stream.get().1 != Token::Struct {
@@ -122,6 +222,7 @@ impl Parser {
             self.stream.next();
             tree.nodes.push(self.parse_t_args()?);
             if self.stream.get().1 == Token::BracketRight {
+                tree.position = tree.position.extend(&self.stream.get().0);
                 self.stream.next();
             } else {
                 return Err(self.stream.get().replace("Expecting `]`".to_owned()));
@@ -141,7 +242,8 @@ impl Parser {
 
     fn parse_props_def(&mut self) -> Result<ParseTree, ItemPosition<String>> {
         let mut tree = ParseTree::new(TreeVariant::PropsDef, self.stream.get().0);
-        if let Token::Word(_) = self.stream.get().1 {
+        if let Token::Word(_) = self.stream.get_after_attributes().1 {
+            tree.nodes.push(self.parse_attributes()?);
             tree.nodes.push(self.parse_prop()?);
             tree.nodes.push(self.parse_props_def()?);
         }
@@ -157,9 +259,67 @@ impl Parser {
             return Err(self.stream.get().replace("Expecting `:`".to_owned()));
         }
         tree.nodes.push(self.parse_ptype()?);
+        if let Token::Equals = self.stream.get().1 {
+            self.stream.next();
+            tree.nodes.push(self.parse_value()?);
+        }
         Ok(tree)
     }
 
+    fn parse_value(&mut self) -> Result<ParseTree, ItemPosition<String>> {
+        let literal = match self.stream.consume() {
+            ItemPosition(p, Token::Word(w)) => ItemPosition(*p, Self::word_to_literal(w, *p)?),
+            ItemPosition(p, Token::Str(s)) => ItemPosition(*p, Literal::String(s.clone())),
+            ItemPosition(p, Token::BracketLeft) => {
+                let p = *p;
+                match self.stream.consume() {
+                    ItemPosition(end, Token::BracketRight) => {
+                        ItemPosition(p.extend(end), Literal::EmptyList)
+                    }
+                    end => return Err(end.replace("Expecting `]`".to_owned())),
+                }
+            }
+            ItemPosition(p, Token::Invalid(e)) => return Err(ItemPosition(*p, e.clone())),
+            p => return Err(p.replace("Expecting value".to_owned())),
+        };
+        Ok(ParseTree::new(TreeVariant::Value(literal.1), literal.0))
+    }
+
+    fn word_to_literal(word: &str, position: Position) -> Result<Literal, ItemPosition<String>> {
+        let is_number = word
+            .trim_start_matches('-')
+            .starts_with(|c: char| c.is_ascii_digit());
+        if word == "true" {
+            Ok(Literal::Boolean(true))
+        } else if word == "false" {
+            Ok(Literal::Boolean(false))
+        } else if is_number {
+            if let Ok(v) = word.parse::<i64>() {
+                Ok(Literal::Integer(v))
+            } else if let Some(v) = word.parse::<f64>().ok().filter(|v| v.is_finite()) {
+                // out of range values (`1e400`) are parsed as infinity
+                Ok(Literal::Float(v))
+            } else {
+                Err(ItemPosition(position, "Incorrect number".to_owned()))
+            }
+        } else if let Some((enum_name, variant)) = word.split_once('.') {
+            if enum_name.is_empty() || variant.is_empty() {
+                return Err(ItemPosition(position, "Expecting value".to_owned()));
+            }
+            let enum_length = enum_name.chars().count();
+            let to_name = |name: &str, start: usize| {
+                let position = Position::new(start, name.chars().count());
+                Name::new(name.to_owned(), position).map_err(|e| ItemPosition(position, e))
+            };
+            Ok(Literal::EnumVariant {
+                enum_name: to_name(enum_name, position.start)?,
+                variant: to_name(variant, position.start + enum_length + 1)?,
+            })
+        } else {
+            Err(ItemPosition(position, "Expecting value".to_owned()))
+        }
+    }
+
     fn parse_enum_def(&mut self) -> Result<ParseTree, ItemPosition<String>> {
         let mut tree = ParseTree::new(TreeVariant::EnumDef, self.stream.get().0);
         if self.stream.get().1 != Token::Enum {
@@ -172,9 +332,32 @@ impl Parser {
         Ok(tree)
     }
 
+    fn parse_const_def(&mut self) -> Result<ParseTree, ItemPosition<String>> {
+        let mut tree = ParseTree::new(TreeVariant::ConstDef, self.stream.get().0);
+        if self.stream.get().1 != Token::Const {
+            return Err(self.stream.get().replace("Expecting `.const`".to_owned()));
+        }
+        self.stream.next();
+        tree.nodes.push(self.parse_name()?);
+        if let Token::Colon = self.stream.get().1 {
+            self.stream.next();
+        } else {
+            return Err(self.stream.get().replace("Expecting `:`".to_owned()));
+        }
+        tree.nodes.push(self.parse_ptype()?);
+        if let Token::Equals = self.stream.get().1 {
+            self.stream.next();
+        } else {
+            return Err(self.stream.get().replace("Expecting `=`".to_owned()));
+        }
+        tree.nodes.push(self.parse_value()?);
+        Ok(tree)
+    }
+
     fn parse_variants_def(&mut self) -> Result<ParseTree, ItemPosition<String>> {
         let mut tree = ParseTree::new(TreeVariant::VariantsDef, self.stream.get().0);
-        if let Token::Word(_) = self.stream.get().1 {
+        if let Token::Word(_) = self.stream.get_after_attributes().1 {
+            tree.nodes.push(self.parse_attributes()?);
             tree.nodes.push(self.parse_variant()?);
             tree.nodes.push(self.parse_variants_def()?);
         }
@@ -237,17 +420,77 @@ impl Parser {
         }
     }
 
-    fn tree_to_leaptype(tree: &ParseTree) -> Result<LeapType, ItemPosition<String>> {
+    fn tree_to_definition(tree: &ParseTree) -> Result<LeapDefinition, ItemPosition<String>> {
         // tree -> Start
-        let tree = &tree.nodes[0];
+        let attributes = Self::tree_to_attributes(&tree.nodes[0])?;
+        let tree = &tree.nodes[1];
         match tree.variant {
-            TreeVariant::StructDef => Ok(LeapType::Struct(Self::tree_to_struct(&tree)?)),
-            TreeVariant::EnumDef => Ok(LeapType::Enum(Self::tree_to_enum(&tree)?)),
+            TreeVariant::StructDef => Ok(LeapDefinition::Type(LeapType::Struct(
+                Self::tree_to_struct(tree, attributes)?,
+            ))),
+            TreeVariant::EnumDef => Ok(LeapDefinition::Type(LeapType::Enum(Self::tree_to_enum(
+                tree, attributes,
+            )?))),
+            TreeVariant::ConstDef => Ok(LeapDefinition::Const(Self::tree_to_const(
+                tree, attributes,
+            )?)),
             _ => panic!("Incorrect parse tree"),
         }
     }
 
-    fn tree_to_struct(tree: &ParseTree) -> Result<LeapStruct, ItemPosition<String>> {
+    fn tree_to_const(
+        tree: &ParseTree,
+        attributes: Vec<Attribute>,
+    ) -> Result<LeapConst, ItemPosition<String>> {
+        // tree -> ConstDef
+        let value_type = Self::tree_to_prop_type_simple(&tree.nodes[1])
+            .try_into_prop_type(&[])
+            .map_err(|e| ItemPosition(tree.nodes[1].position, e))?;
+        Ok(LeapConst {
+            name: Self::tree_to_def_name(&tree.nodes[0])?,
+            value_type,
+            value: Self::tree_to_literal(&tree.nodes[2]),
+            attributes,
+            // comments are attached after parsing
+            doc: None,
+            // path is unknown at this point
+            path: "".to_owned(),
+            position: tree.position,
+        })
+    }
+
+    fn tree_to_attributes(tree: &ParseTree) -> Result<Vec<Attribute>, ItemPosition<String>> {
+        // tree -> Attributes
+        let mut attributes = vec![];
+        let mut tree = tree;
+        while !tree.nodes.is_empty() {
+            attributes.push(Self::tree_to_attribute(&tree.nodes[0])?);
+            tree = &tree.nodes[1];
+        }
+        Ok(attributes)
+    }
+
+    fn tree_to_attribute(tree: &ParseTree) -> Result<Attribute, ItemPosition<String>> {
+        // tree -> Attribute
+        if let TreeVariant::Attribute(text) = &tree.variant {
+            let (name, value) = match text.split_once(char::is_whitespace) {
+                Some((name, value)) => (name, Some(value.trim().to_owned())),
+                None => (text.as_str(), None),
+            };
+            Ok(Attribute {
+                name: name.to_owned(),
+                value,
+                position: tree.position,
+            })
+        } else {
+            panic!("Incorrect parse tree");
+        }
+    }
+
+    fn tree_to_struct(
+        tree: &ParseTree,
+        attributes: Vec<Attribute>,
+    ) -> Result<LeapStruct, ItemPosition<String>> {
         // tree -> StructDef
         let args = if tree.nodes[1].nodes.is_empty() {
             vec![]
@@ -266,6 +509,9 @@ impl Parser {
                     Ok(prop_type) => Ok(Prop {
                         name: prop_simple.name,
                         prop_type,
+                        default: prop_simple.default,
+                        attributes: prop_simple.attributes,
+                        doc: None,
                         position: prop_simple.position,
                         // it is unknown curerntly if property is recursive
                         is_recursive: false,
@@ -275,9 +521,12 @@ impl Parser {
             )
             .collect::<Result<_, _>>()?;
         Ok(LeapStruct {
-            name: Self::tree_to_name(&tree.nodes[0])?,
+            name: Self::tree_to_def_name(&tree.nodes[0])?,
             args,
             props,
+            attributes,
+            // comments are attached after parsing
+            doc: None,
             // path is unknown at this point
             path: "".to_owned(),
             position: tree.position,
@@ -299,7 +548,10 @@ impl Parser {
         Ok(args)
     }
 
-    fn tree_to_enum(tree: &ParseTree) -> Result<LeapEnum, ItemPosition<String>> {
+    fn tree_to_enum(
+        tree: &ParseTree,
+        attributes: Vec<Attribute>,
+    ) -> Result<LeapEnum, ItemPosition<String>> {
         // tree -> EnumDef
         let args = if tree.nodes[1].nodes.is_empty() {
             vec![]
@@ -315,10 +567,19 @@ impl Parser {
             .into_iter()
             .map(|p| {
                 let position = p.position;
+                if p.default.is_some() {
+                    return Err(ItemPosition(
+                        position,
+                        "Enum variant can't have default value".to_owned(),
+                    ));
+                }
                 match p.prop_type_simple.try_into_prop_type(&args) {
                     Ok(prop_type) => Ok(Prop {
                         name: p.name,
                         prop_type,
+                        default: None,
+                        attributes: p.attributes,
+                        doc: None,
                         position,
                         // it is unknown curerntly if property is recursive
                         is_recursive: false,
@@ -328,9 +589,12 @@ impl Parser {
             })
             .collect::<Result<_, _>>()?;
         Ok(LeapEnum {
-            name: Self::tree_to_name(&tree.nodes[0])?,
+            name: Self::tree_to_def_name(&tree.nodes[0])?,
             args,
             variants,
+            attributes,
+            // comments are attached after parsing
+            doc: None,
             // path is unknown at this point
             path: "".to_owned(),
             position: tree.position,
@@ -342,9 +606,10 @@ impl Parser {
         let mut props = vec![];
         let mut tree = tree;
         loop {
-            let prop_tree = &tree.nodes[0];
-            props.push(Self::tree_to_simple_prop(prop_tree)?);
-            tree = &tree.nodes[1];
+            let attributes = Self::tree_to_attributes(&tree.nodes[0])?;
+            let prop_tree = &tree.nodes[1];
+            props.push(Self::tree_to_simple_prop(prop_tree, attributes)?);
+            tree = &tree.nodes[2];
             if tree.nodes.is_empty() {
                 break;
             }
@@ -352,11 +617,16 @@ impl Parser {
         Ok(props)
     }
 
-    fn tree_to_simple_prop(tree: &ParseTree) -> Result<PropSimple, ItemPosition<String>> {
+    fn tree_to_simple_prop(
+        tree: &ParseTree,
+        attributes: Vec<Attribute>,
+    ) -> Result<PropSimple, ItemPosition<String>> {
         // tree -> Prop
         Ok(PropSimple {
             name: Self::tree_to_name(&tree.nodes[0])?,
             prop_type_simple: Self::tree_to_prop_type_simple(&tree.nodes[1]),
+            default: tree.nodes.get(2).map(Self::tree_to_literal),
+            attributes,
             position: tree.position,
         })
     }
@@ -366,17 +636,22 @@ impl Parser {
         let mut variants = vec![];
         let mut tree = tree;
         loop {
-            let variant_tree = &tree.nodes[0].nodes[0];
+            let attributes = Self::tree_to_attributes(&tree.nodes[0])?;
+            let variant_tree = &tree.nodes[1].nodes[0];
             match variant_tree.variant {
-                TreeVariant::Prop => variants.push(Self::tree_to_simple_prop(variant_tree)?),
+                TreeVariant::Prop => {
+                    variants.push(Self::tree_to_simple_prop(variant_tree, attributes)?)
+                }
                 TreeVariant::PType => variants.push(PropSimple {
                     name: Self::tree_to_name(&variant_tree.nodes[0])?,
                     prop_type_simple: Self::tree_to_prop_type_simple(variant_tree),
+                    default: None,
+                    attributes,
                     position: variant_tree.position,
                 }),
                 _ => panic!("Incorrect parse tree"),
             }
-            tree = &tree.nodes[1];
+            tree = &tree.nodes[2];
             if tree.nodes.is_empty() {
                 break;
             }
@@ -417,6 +692,27 @@ impl Parser {
         args
     }
 
+    fn tree_to_literal(tree: &ParseTree) -> Literal {
+        // tree -> Value
+        if let TreeVariant::Value(literal) = &tree.variant {
+            literal.clone()
+        } else {
+            panic!("Incorrect parse tree");
+        }
+    }
+
+    // name of type or constant, builtin types can't be redefined
+    fn tree_to_def_name(tree: &ParseTree) -> Result<Name, ItemPosition<String>> {
+        let name = Self::tree_to_name(tree)?;
+        if is_builtin_name(name.get()) {
+            return Err(ItemPosition(
+                name.position,
+                format!("Builtin type `{}` can't be redefined", name),
+            ));
+        }
+        Ok(name)
+    }
+
     fn tree_to_name(tree: &ParseTree) -> Result<Name, ItemPosition<String>> {
         // tree -> Name
         if let TreeVariant::Name(n) = &tree.variant {
@@ -431,7 +727,7 @@ impl Parser {
 mod tests {
 
     use super::*;
-    use crate::leaptypes::ValueType;
+    use crate::leaptypes::{SimpleType, ValueType};
 
     #[test]
     fn test_empty_spec() {
@@ -442,7 +738,7 @@ mod tests {
     #[test]
     fn test_simple_error() {
         let r = &Parser::parse("aaa bbb ccc");
-        assert!(matches!(r, Err(_)));
+        assert!(r.is_err());
     }
 
     #[test]
@@ -456,6 +752,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_position_of_next_type() {
+        let types =
+            &Parser::parse(".struct aaa\n.struct bbb[t]\n\n.enum ccc\n@since 1\n.struct ddd")
+                .unwrap();
+        assert_eq!(types[0].position().length, 11);
+        assert_eq!(types[1].position().length, 14);
+        assert_eq!(types[2].position().length, 9);
+    }
+
     #[test]
     fn test_parse_struct_with_args() {
         let s = &Parser::parse(".struct aaa[a]").unwrap()[0];
@@ -616,6 +922,249 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_extended_simple_types() {
+        let s = &Parser::parse(
+            ".struct aaa\n    a: i32\n    b: u64\n    c: bytes\n    d: decimal\n    e: date\n    f: datetime\n    g: duration\n    h: uuid",
+        )
+        .unwrap()[0];
+        let s = s.as_struct().unwrap();
+        let types: Vec<_> = s.props.iter().map(|p| p.prop_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                ValueType::Simple(SimpleType::Int32, Position::default()),
+                ValueType::Simple(SimpleType::UInt64, Position::default()),
+                ValueType::Simple(SimpleType::Bytes, Position::default()),
+                ValueType::Simple(SimpleType::Decimal, Position::default()),
+                ValueType::Simple(SimpleType::Date, Position::default()),
+                ValueType::Simple(SimpleType::DateTime, Position::default()),
+                ValueType::Simple(SimpleType::Duration, Position::default()),
+                ValueType::Simple(SimpleType::Uuid, Position::default()),
+            ]
+        );
+        assert!(Parser::parse(".struct aaa\n    a: uuid[int]").is_err());
+    }
+
+    #[test]
+    fn test_parse_builtin_names() {
+        // user type would be shadowed by the builtin in references
+        let e = Parser::parse(".struct date\n    a: int\n.struct b\n    x: date").unwrap_err();
+        assert_eq!(
+            e,
+            ItemPosition(
+                Position::new(8, 4),
+                "Builtin type `date` can't be redefined".to_owned()
+            )
+        );
+        let e = Parser::parse_definitions(".enum list\n.const u8: int = 1").unwrap_err();
+        assert_eq!(e.0, Position::new(6, 4));
+        let e = Parser::parse_definitions(".const u8: int = 1").unwrap_err();
+        assert_eq!(e.0, Position::new(7, 2));
+        assert!(Parser::parse(".struct dates\n    date: date").is_ok());
+    }
+
+    #[test]
+    fn test_parse_attributes() {
+        let types = Parser::parse(
+            "
+            @deprecated
+            @since 2.3
+            .struct aaa
+                @json-name userId
+                id: int
+                name: str
+            @format  email
+            .enum bbb
+                @min 0
+                aaa
+        ",
+        )
+        .unwrap();
+        let s = types[0].as_struct().unwrap();
+        assert_eq!(s.attributes.len(), 2);
+        assert_eq!(s.attributes[0].name, "deprecated");
+        assert_eq!(s.attributes[0].value, None);
+        assert_eq!(s.attributes[0].position.start, 13);
+        assert_eq!(s.attributes[0].position.length, 11);
+        assert_eq!(s.attributes[1].name, "since");
+        assert_eq!(s.attributes[1].value.as_deref(), Some("2.3"));
+        assert_eq!(s.position.start, 60);
+        assert_eq!(
+            s.props[0]
+                .get_attribute("json-name")
+                .unwrap()
+                .value
+                .as_deref(),
+            Some("userId")
+        );
+        assert!(s.props[1].attributes.is_empty());
+        let e = types[1].as_enum().unwrap();
+        assert_eq!(
+            types[1].get_attribute("format").unwrap().value.as_deref(),
+            Some("email")
+        );
+        assert_eq!(e.variants[0].attributes[0].name, "min");
+        assert!(Parser::parse(".struct aaa\n    @deprecated").is_err());
+        assert!(Parser::parse("@ deprecated\n.struct aaa").is_err());
+    }
+
+    #[test]
+    fn test_parse_attribute_on_same_line() {
+        let e = Parser::parse(".struct s\n    a: int @min 0\n    b: int").unwrap_err();
+        assert_eq!(
+            e,
+            ItemPosition(
+                Position::new(21, 6),
+                "Attribute should be on its own line".to_owned()
+            )
+        );
+        let e = Parser::parse(".struct s\n    a: int @min 0").unwrap_err();
+        assert_eq!(e.0, Position::new(21, 6));
+        let e = Parser::parse(".struct s @deprecated\n    a: int").unwrap_err();
+        assert_eq!(e.0, Position::new(10, 11));
+        assert!(Parser::parse(".struct s\n    a: int // c\n    @min 0\n    b: int").is_ok());
+    }
+
+    #[test]
+    fn test_parse_defaults() {
+        let s = &Parser::parse(
+            ".struct aaa\n    a: int = -3\n    b: float = 1e3\n    c: str = \"t\\\"t\"\n    d: bool = false\n    e: list[int] = []\n    f: log-mode = log-mode.info\n    g: int",
+        )
+        .unwrap()[0];
+        let s = s.as_struct().unwrap();
+        let defaults: Vec<_> = s.props.iter().map(|p| p.default.clone()).collect();
+        assert_eq!(defaults[0], Some(Literal::Integer(-3)));
+        assert_eq!(defaults[1], Some(Literal::Float(1000.0)));
+        assert_eq!(defaults[2], Some(Literal::String("t\"t".to_owned())));
+        assert_eq!(defaults[3], Some(Literal::Boolean(false)));
+        assert_eq!(defaults[4], Some(Literal::EmptyList));
+        if let Some(Literal::EnumVariant { enum_name, variant }) = &defaults[5] {
+            assert_eq!(enum_name.get(), "log-mode");
+            assert_eq!(enum_name.position.start, 127);
+            assert_eq!(variant.get(), "info");
+            assert_eq!(variant.position.start, 136);
+        } else {
+            panic!("expecting enum variant");
+        }
+        assert_eq!(defaults[6], None);
+        // position of property includes default value
+        assert_eq!(s.props[0].position.length, 11);
+        assert!(Parser::parse(".struct aaa\n    a: int = ").is_err());
+        assert!(Parser::parse(".struct aaa\n    a: int = abc").is_err());
+        assert_eq!(
+            Parser::parse(".struct aaa\n    a: float = 1e400")
+                .unwrap_err()
+                .1,
+            "Incorrect number"
+        );
+        assert!(Parser::parse(".struct aaa\n    a: str = \"abc").is_err());
+        assert!(Parser::parse(".enum aaa\n    a: bbb = 1").is_err());
+        // values are checked on parse, enums from other files are checked with the whole spec
+        assert_eq!(
+            Parser::parse(".struct s\n    a: u8 = 1000").unwrap_err(),
+            ItemPosition(
+                Position::new(14, 12),
+                "Value `1000` can't be used for type `u8`".to_owned()
+            )
+        );
+        assert!(Parser::parse(".struct s\n    a: uuid = \"x\"").is_err());
+        assert!(Parser::parse(".struct s\n    a: e = e.b\n.enum e\n    a").is_err());
+        assert!(Parser::parse(".struct s\n    a: e = e.b").is_ok());
+    }
+
+    #[test]
+    fn test_parse_paths_defaults() {
+        let path = std::env::temp_dir().join("leap-test-parse-paths-defaults.leap");
+        std::fs::write(&path, ".struct aaa\n    a: int = 1\n    b: str = 2").unwrap();
+        let path = path.to_str().unwrap();
+        let e = Parser::parse_paths_iter([path].into_iter()).unwrap_err();
+        assert_eq!(e.path, path);
+        assert_eq!(e.position, 31);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_paths_consts() {
+        let path = std::env::temp_dir().join("leap-test-parse-paths-consts.leap");
+        std::fs::write(&path, ".const a: int = 1\n.const b: bool = 1").unwrap();
+        let path = path.to_str().unwrap();
+        let e = Parser::parse_paths_iter([path].into_iter()).unwrap_err();
+        assert_eq!(e.path, path);
+        assert_eq!(e.position, 18);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_consts() {
+        let definitions = Parser::parse_definitions(
+            "
+            .const max-page-size: int = 100
+            .struct aaa
+            @since 2
+            .const version: str = \"1.2\"
+        ",
+        )
+        .unwrap();
+        assert_eq!(definitions.len(), 3);
+        let c = definitions[0].as_const().unwrap();
+        assert_eq!(c.name.get(), "max-page-size");
+        assert_eq!(
+            c.value_type,
+            ValueType::Simple(SimpleType::Integer, Position::default())
+        );
+        assert_eq!(c.value, Literal::Integer(100));
+        assert_eq!(c.position.start, 13);
+        assert_eq!(c.position.length, 31);
+        assert!(definitions[1].as_type().is_some());
+        let c = definitions[2].as_const().unwrap();
+        assert_eq!(c.attributes[0].name, "since");
+        assert_eq!(c.value, Literal::String("1.2".to_owned()));
+        // consts are skipped by `parse`
+        let types = Parser::parse(".const a: int = 1\n.struct b").unwrap();
+        assert_eq!(types.len(), 1);
+        assert!(Parser::parse(".const a: int").is_err());
+        assert!(Parser::parse(".const a = 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_doc_comments() {
+        let definitions = Parser::parse_definitions(
+            "
+            / not attached
+
+            /-- page
+            /-- of items
+            @since 2
+            .struct page[t] /-- trail
+                items: list[t] / items
+                / total
+                total: int
+                @min 0
+                / not attached
+                size: int
+            / value
+            .enum value
+                / page variant
+                page[int]
+                none
+            .const max-size: int = 10 / max
+        ",
+        )
+        .unwrap();
+        let s = definitions[0].as_type().unwrap().as_struct().unwrap();
+        assert_eq!(s.doc.as_deref(), Some("page\nof items\ntrail"));
+        assert_eq!(s.props[0].doc.as_deref(), Some("items"));
+        assert_eq!(s.props[1].doc.as_deref(), Some("total"));
+        assert_eq!(s.props[2].doc, None);
+        let e = definitions[1].as_type().unwrap().as_enum().unwrap();
+        assert_eq!(e.doc.as_deref(), Some("value"));
+        assert_eq!(e.variants[0].doc.as_deref(), Some("page variant"));
+        assert_eq!(e.variants[1].doc, None);
+        let c = definitions[2].as_const().unwrap();
+        assert_eq!(c.doc.as_deref(), Some("max"));
+    }
+
     #[test]
     fn test_err_position_simple() {
         let e = Parser::parse("aaa");
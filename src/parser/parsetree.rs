@@ -22,8 +22,20 @@ impl ParseTree {
         for n in &mut self.nodes {
             n.calc_length();
         }
-        if let Some(last) = self.nodes.last() {
+        // empty node gets position of the next token, which is not part of the tree
+        if let Some(last) = self.nodes.iter().rev().find(|n| !n.is_empty()) {
             self.position = self.position.extend(&last.position);
         }
     }
+
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+            && matches!(
+                self.variant,
+                TreeVariant::Attributes
+                    | TreeVariant::TArgsDef
+                    | TreeVariant::PropsDef
+                    | TreeVariant::VariantsDef
+            )
+    }
 }
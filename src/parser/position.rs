@@ -1,7 +1,8 @@
 use std::cmp::max;
 
 // todo: path only in struct/enum level
-#[derive(PartialEq, Clone, Copy, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub start: usize,  // index of start utf8 character
     pub length: usize, // length in utf8 characters
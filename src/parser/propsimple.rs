@@ -1,9 +1,11 @@
-use crate::leaptypes::Name;
 use super::{position::Position, proptypesimple::PropTypeSimple};
+use crate::leaptypes::{Attribute, Literal, Name};
 
 #[derive(Debug)]
 pub struct PropSimple {
     pub name: Name,
     pub prop_type_simple: PropTypeSimple,
+    pub default: Option<Literal>,
+    pub attributes: Vec<Attribute>,
     pub position: Position,
 }
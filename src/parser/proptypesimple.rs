@@ -1,4 +1,4 @@
-use crate::leaptypes::{Name, ValueType, SimpleType};
+use crate::leaptypes::{Name, SimpleType, ValueType};
 
 use super::position::Position;
 
@@ -12,44 +12,25 @@ pub struct PropTypeSimple {
 impl PropTypeSimple {
     pub fn try_into_prop_type(mut self, type_args: &[Name]) -> Result<ValueType, String> {
         match self.name.as_str() {
-            "str" => {
-                if self.args.is_empty() {
-                    Ok(ValueType::Simple(SimpleType::String))
-                } else {
-                    Err("String type should not have arguments".to_owned())
-                }
-            }
-            "int" => {
-                if self.args.is_empty() {
-                    Ok(ValueType::Simple(SimpleType::Integer))
-                } else {
-                    Err("Integer type should not have arguments".to_owned())
-                }
-            }
-            "float" => {
-                if self.args.is_empty() {
-                    Ok(ValueType::Simple(SimpleType::Float))
-                } else {
-                    Err("Float type should not have arguments".to_owned())
-                }
-            }
-            "bool" => {
-                if self.args.is_empty() {
-                    Ok(ValueType::Simple(SimpleType::Boolean))
-                } else {
-                    Err("Boolean type should not have arguments".to_owned())
-                }
-            }
             "list" => {
                 if self.args.len() == 1 {
                     let list_element = self.args.remove(0).try_into_prop_type(type_args)?;
-                    Ok(ValueType::List(Box::new(list_element)))
+                    Ok(ValueType::List(Box::new(list_element), self.position))
                 } else {
                     Err("List should have exactly one argument".to_owned())
                 }
             }
             name => {
-                let name = Name::new(name.to_owned(), self.position)?;
+                if let Some(simple_type) = SimpleType::from_name(name) {
+                    return if self.args.is_empty() {
+                        Ok(ValueType::Simple(simple_type, self.position))
+                    } else {
+                        Err(format!("`{}` type should not have arguments", name))
+                    };
+                }
+                // position of name only, without arguments
+                let position = Position::new(self.position.start, name.chars().count());
+                let name = Name::new(name.to_owned(), position)?;
                 if type_args.contains(&name) {
                     if self.args.is_empty() {
                         Ok(ValueType::TypeArg(name))
@@ -62,7 +43,11 @@ impl PropTypeSimple {
                         .into_iter()
                         .map(|a| a.try_into_prop_type(type_args))
                         .collect::<Result<_, _>>()?;
-                    Ok(ValueType::LeapType { name, args })
+                    Ok(ValueType::LeapType {
+                        name,
+                        args,
+                        position: self.position,
+                    })
                 }
             }
         }
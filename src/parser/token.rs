@@ -5,11 +5,21 @@ pub enum Token {
     Struct,
     // ".enum"
     Enum,
+    // ".const"
+    Const,
     // "["
     BracketLeft,
     // "]"
     BracketRight,
     // ":"
     Colon,
+    // "="
+    Equals,
+    // "\"text\"", unescaped text of string literal
+    Str(String),
+    // "@name value", text after `@` up to the end of line or comment
+    Attribute(String),
+    // malformed token with description of the problem
+    Invalid(String),
     End,
-}
\ No newline at end of file
+}
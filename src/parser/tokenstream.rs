@@ -1,85 +1,56 @@
-use super::{itemposition::ItemPosition, token::Token};
+use super::{
+    cst::{self, SyntaxToken, TokenKind},
+    itemposition::ItemPosition,
+    token::Token,
+};
 
 pub struct TokenStream {
     cursor: usize,
     tokens: Vec<ItemPosition<Token>>,
     end: ItemPosition<Token>,
+    // char indexes of line breaks
+    newlines: Vec<usize>,
 }
 
 impl TokenStream {
     pub fn new(data: &str) -> Self {
+        let syntax_tokens = cst::tokenize(data);
         Self {
             cursor: 0,
-            tokens: Self::tokenize(data),
+            newlines: syntax_tokens
+                .iter()
+                .filter(|t| t.kind == TokenKind::Newline)
+                .map(|t| t.position.start)
+                .collect(),
+            tokens: Self::from_syntax_tokens(syntax_tokens),
             // end position for return if tokens is empty
             end: ItemPosition::new(0, 0, Token::End),
         }
     }
 
-    pub fn tokenize(data: &str) -> Vec<ItemPosition<Token>> {
-        let mut tokens: Vec<ItemPosition<Token>> = Vec::new();
-        let mut word = String::new();
-        let mut word_index = 0;
-        let mut is_comment = false;
-        for (i, v) in data.chars().enumerate() {
-            if is_comment {
-                if v == '\n' {
-                    is_comment = false;
-                }
-                continue;
-            }
-            match v {
-                '[' => {
-                    tokens.push(ItemPosition::new(word_index, word.len(), Token::Word(word)));
-                    tokens.push(ItemPosition::new(i, 1, Token::BracketLeft));
-                    word = String::new();
-                }
-                ']' => {
-                    tokens.push(ItemPosition::new(word_index, word.len(), Token::Word(word)));
-                    tokens.push(ItemPosition::new(i, 1, Token::BracketRight));
-                    word = String::new();
-                }
-                ':' => {
-                    tokens.push(ItemPosition::new(word_index, word.len(), Token::Word(word)));
-                    tokens.push(ItemPosition::new(i, 1, Token::Colon));
-                    word = String::new();
-                }
-                '/' => {
-                    tokens.push(ItemPosition::new(word_index, word.len(), Token::Word(word)));
-                    word = String::new();
-                    is_comment = true;
-                }
-                _ => {
-                    // todo: word should start with letter (check in Name?)
-                    if v.is_alphanumeric() || v == '-' || v == '.' {
-                        if word.is_empty() {
-                            word_index = i;
-                        }
-                        word.push(v)
-                    } else {
-                        tokens.push(ItemPosition::new(word_index, word.len(), Token::Word(word)));
-                        word = String::new();
-                    }
-                }
-            }
-        }
-        tokens.push(ItemPosition::new(word_index, word.len(), Token::Word(word)));
-        tokens = tokens
+    // drops whitespace and comments, unescapes strings
+    fn from_syntax_tokens(syntax_tokens: Vec<SyntaxToken>) -> Vec<ItemPosition<Token>> {
+        let mut tokens: Vec<ItemPosition<Token>> = syntax_tokens
             .into_iter()
-            .filter(|t| {
-                if let ItemPosition(.., Token::Word(w)) = t {
-                    !w.is_empty()
-                } else {
-                    true
-                }
-            })
-            .map(|t| match &t {
-                ItemPosition(.., Token::Word(w)) => match w.as_str() {
-                    ".struct" => t.replace(Token::Struct),
-                    ".enum" => t.replace(Token::Enum),
-                    _ => t,
-                },
-                _ => t,
+            .filter(|t| !t.is_trivia())
+            .map(|t| {
+                let token = match t.kind {
+                    TokenKind::Struct => Token::Struct,
+                    TokenKind::Enum => Token::Enum,
+                    TokenKind::Const => Token::Const,
+                    TokenKind::BracketLeft => Token::BracketLeft,
+                    TokenKind::BracketRight => Token::BracketRight,
+                    TokenKind::Colon => Token::Colon,
+                    TokenKind::Equals => Token::Equals,
+                    TokenKind::Str => match unescape(&t.text) {
+                        Some(text) => Token::Str(text),
+                        None => Token::Invalid("Unterminated string".to_owned()),
+                    },
+                    // text without `@`
+                    TokenKind::Attribute => Token::Attribute(t.text[1..].to_owned()),
+                    _ => Token::Word(t.text),
+                };
+                ItemPosition(t.position, token)
             })
             .collect();
         // End token goes right after the last token, in order to show positional
@@ -110,6 +81,26 @@ impl TokenStream {
         self.get_by_index(self.cursor + 1)
     }
 
+    // current token is the first one on its line
+    pub fn starts_line(&self) -> bool {
+        if self.cursor == 0 {
+            return true;
+        }
+        let previous_end = self.get_by_index(self.cursor - 1).0.end();
+        let start = self.get().0.start;
+        let i = self.newlines.partition_point(|n| *n < previous_end);
+        self.newlines.get(i).is_some_and(|n| *n < start)
+    }
+
+    // first token after attributes starting from current position
+    pub fn get_after_attributes(&self) -> &ItemPosition<Token> {
+        let mut i = self.cursor;
+        while let ItemPosition(.., Token::Attribute(_)) = self.get_by_index(i) {
+            i += 1;
+        }
+        self.get_by_index(i)
+    }
+
     pub fn get_by_index(&self, i: usize) -> &ItemPosition<Token> {
         self.tokens.get(i).unwrap_or_else(|| self.get_end())
     }
@@ -122,6 +113,24 @@ impl TokenStream {
     }
 }
 
+// text of string literal without quotes and escapes, none if closing quote is missing
+fn unescape(literal: &str) -> Option<String> {
+    let mut text = String::new();
+    let mut chars = literal.chars().skip(1);
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => text.push(match chars.next()? {
+                'n' => '\n',
+                't' => '\t',
+                c => c,
+            }),
+            '"' => return Some(text),
+            c => text.push(c),
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -130,7 +139,7 @@ mod tests {
     #[test]
     fn test_tokenizer_empty_struct() {
         let text = ".struct aa-aa";
-        let tokens = TokenStream::tokenize(text);
+        let tokens = TokenStream::new(text).tokens;
         assert_eq!(
             tokens,
             vec![
@@ -144,7 +153,7 @@ mod tests {
     #[test]
     fn test_tokenizer_empty_enum() {
         let text = ".enum aaa";
-        let tokens = TokenStream::tokenize(text);
+        let tokens = TokenStream::new(text).tokens;
         assert_eq!(
             tokens,
             vec![
@@ -158,7 +167,7 @@ mod tests {
     #[test]
     fn test_tokenizer_struct() {
         let text = ".struct aaa[a b]\n    a: int\n    b: str\n/ comment\n    c: bool";
-        let tokens = TokenStream::tokenize(text);
+        let tokens = TokenStream::new(text).tokens;
         assert_eq!(
             tokens,
             vec![
@@ -181,4 +190,83 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_tokenizer_default_values() {
+        let text = "a: str = \"x/\\\"y\" b: list[int] = []\nc: str = \"aaa";
+        let tokens = TokenStream::new(text).tokens;
+        assert_eq!(
+            tokens,
+            vec![
+                ItemPosition::new(0, 1, Token::Word("a".to_owned())),
+                ItemPosition::new(1, 1, Token::Colon),
+                ItemPosition::new(3, 3, Token::Word("str".to_owned())),
+                ItemPosition::new(7, 1, Token::Equals),
+                ItemPosition::new(9, 7, Token::Str("x/\"y".to_owned())),
+                ItemPosition::new(17, 1, Token::Word("b".to_owned())),
+                ItemPosition::new(18, 1, Token::Colon),
+                ItemPosition::new(20, 4, Token::Word("list".to_owned())),
+                ItemPosition::new(24, 1, Token::BracketLeft),
+                ItemPosition::new(25, 3, Token::Word("int".to_owned())),
+                ItemPosition::new(28, 1, Token::BracketRight),
+                ItemPosition::new(30, 1, Token::Equals),
+                ItemPosition::new(32, 1, Token::BracketLeft),
+                ItemPosition::new(33, 1, Token::BracketRight),
+                ItemPosition::new(35, 1, Token::Word("c".to_owned())),
+                ItemPosition::new(36, 1, Token::Colon),
+                ItemPosition::new(38, 3, Token::Word("str".to_owned())),
+                ItemPosition::new(42, 1, Token::Equals),
+                ItemPosition::new(44, 4, Token::Invalid("Unterminated string".to_owned())),
+                ItemPosition::new(48, 0, Token::End)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_unicode() {
+        let text = ".struct имя";
+        let tokens = TokenStream::new(text).tokens;
+        assert_eq!(
+            tokens,
+            vec![
+                ItemPosition::new(0, 7, Token::Struct),
+                ItemPosition::new(8, 3, Token::Word("имя".to_owned())),
+                ItemPosition::new(11, 0, Token::End)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenizer_attributes() {
+        let text = "@deprecated\n.struct aaa\n    @since  2.3  / comment\n    a: int @min 0";
+        let tokens = TokenStream::new(text).tokens;
+        assert_eq!(
+            tokens,
+            vec![
+                ItemPosition::new(0, 11, Token::Attribute("deprecated".to_owned())),
+                ItemPosition::new(12, 7, Token::Struct),
+                ItemPosition::new(20, 3, Token::Word("aaa".to_owned())),
+                ItemPosition::new(28, 11, Token::Attribute("since  2.3".to_owned())),
+                ItemPosition::new(55, 1, Token::Word("a".to_owned())),
+                ItemPosition::new(56, 1, Token::Colon),
+                ItemPosition::new(58, 3, Token::Word("int".to_owned())),
+                ItemPosition::new(62, 6, Token::Attribute("min 0".to_owned())),
+                ItemPosition::new(68, 0, Token::End)
+            ]
+        );
+        // `/` inside of value is not a comment
+        let tokens = TokenStream::new(
+            "@format http://x/y / comment
+@a/b",
+        )
+        .tokens;
+        assert_eq!(
+            tokens,
+            vec![
+                ItemPosition::new(0, 18, Token::Attribute("format http://x/y".to_owned())),
+                ItemPosition::new(29, 4, Token::Attribute("a/b".to_owned())),
+                ItemPosition::new(33, 0, Token::End)
+            ]
+        );
+    }
 }
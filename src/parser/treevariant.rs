@@ -1,6 +1,10 @@
+use crate::leaptypes::Literal;
+
 #[derive(Debug)]
 pub enum TreeVariant {
     Start,
+    Attributes,
+    Attribute(String),
     Name(String),
     StructDef,
     TArgsDef,
@@ -10,7 +14,9 @@ pub enum TreeVariant {
     EnumDef,
     VariantsDef,
     Variant,
+    ConstDef,
     PType,
     PTArgsBlock,
     PTArgs,
-}
\ No newline at end of file
+    Value(Literal),
+}
@@ -30,12 +30,11 @@ impl From<PropTypeSimple> for ValueType {
     fn from(item: PropTypeSimple) -> Self {
         let mut item = item;
         match item.name.as_str() {
-            "str" => Self::Simple(SimpleType::String),
-            "int" => Self::Simple(SimpleType::Integer),
-            "float" => Self::Simple(SimpleType::Float),
-            "bool" => Self::Simple(SimpleType::Boolean),
             "list" => Self::List(Box::new(Self::from(item.args.remove(0)))),
             name => {
+                if let Some(t) = SimpleType::from_name(name) {
+                    return Self::Simple(t);
+                }
                 let name = Name::new(name.to_owned(), item.position).unwrap();
                 let args = item.args.into_iter().map(Self::from).collect();
                 Self::LeapType { name, args }
@@ -60,7 +59,7 @@ pub struct ValueTypeParser {
 
 impl ValueTypeParser {
     pub fn parse(data: &str) -> Result<ValueType, ItemPosition<String>> {
-        let stream = TokenStream::new(&data);
+        let stream = TokenStream::new(data);
         let mut parser = ValueTypeParser { stream };
         let tree = parser.parse_ptype()?;
         let value_type = Self::tree_to_prop_type_simple(&tree);
@@ -157,6 +156,15 @@ mod tests {
         assert!(matches!(vt, ValueType::Simple(SimpleType::Integer)));
     }
 
+    #[test]
+    fn test_extended_simple_type() {
+        let vt = ValueTypeParser::parse("list[datetime]").unwrap();
+        assert!(matches!(
+            vt.args()[0],
+            ValueType::Simple(SimpleType::DateTime)
+        ));
+    }
+
     #[test]
     fn test_complex_type() {
         let vt = ValueTypeParser::parse("some-class[int aaa[str]]").unwrap();
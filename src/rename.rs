@@ -0,0 +1,284 @@
+use std::collections::BTreeMap;
+
+use crate::leaptypes::{is_builtin_name, LeapEnum, LeapSpec, LeapType, Prop, ValueType};
+use crate::symbols::{self, References, Symbol, SymbolKind};
+use crate::textedit::TextEdit;
+
+// text edits by file path, which rename type, type argument, prop, variant or constant
+// at position (definition or any of its references) to the new name
+pub fn rename(
+    spec: &LeapSpec,
+    path: &str,
+    position: usize,
+    new_name: &str,
+) -> Result<BTreeMap<String, Vec<TextEdit>>, String> {
+    check_name(new_name)?;
+    let mut symbol = symbols::symbol_at(spec, path, position)
+        .ok_or_else(|| "Nothing to rename at position".to_owned())?;
+    let name = symbol.name;
+    if symbol.kind == SymbolKind::Builtin {
+        return Err(format!("Builtin type `{}` can't be renamed", name));
+    }
+    let Some((definition_path, definition)) = symbols::definition(spec, path, &symbol) else {
+        return Err(format!("Definition of `{}` not found", name));
+    };
+    // variant without own name is renamed together with its type
+    if symbol.kind == SymbolKind::VariantRef {
+        if let Some(s) = symbols::symbol_at(spec, definition_path, definition.start)
+            .filter(|s| s.kind == SymbolKind::TypeRef)
+        {
+            symbol = s;
+        }
+    }
+    let mut edits = BTreeMap::new();
+    if name == new_name {
+        return Ok(edits);
+    }
+    let spec_references = References::new(spec);
+    let mut references = spec_references.get(path, &symbol).to_vec();
+    let exists = |kind: &str| Err(format!("{} `{}` already exists", kind, new_name));
+    match symbol.kind {
+        SymbolKind::Type | SymbolKind::TypeRef => {
+            check_new_type_name(spec, new_name)?;
+            for (path, owner, variant) in check_type_references(spec, name, new_name)? {
+                let variant = Symbol {
+                    kind: SymbolKind::Variant,
+                    name: variant.name.get(),
+                    position: variant.name.position,
+                    owner: Some(&owner.name),
+                };
+                // definition of the variant is renamed as reference to the type
+                references.extend(
+                    spec_references
+                        .get(path, &variant)
+                        .iter()
+                        .filter(|(_, s)| s.kind == SymbolKind::VariantRef),
+                );
+            }
+        }
+        SymbolKind::TypeArg => {
+            if has_arg(
+                symbol.owner.and_then(|o| spec.get_type_by_name(o.get())),
+                new_name,
+            ) {
+                return exists("Type argument");
+            }
+            if spec.get_type_by_name(new_name).is_some() {
+                return exists("Type");
+            }
+            if is_builtin_name(new_name) {
+                return exists("Builtin type");
+            }
+        }
+        SymbolKind::Prop | SymbolKind::Variant | SymbolKind::VariantRef => {
+            let props = match symbol.owner.and_then(|o| spec.get_type_by_name(o.get())) {
+                Some(LeapType::Struct(s)) => &s.props,
+                Some(LeapType::Enum(e)) => &e.variants,
+                None => return Err(format!("Definition of `{}` not found", name)),
+            };
+            if props.iter().any(|p| p.name.get() == new_name) {
+                return exists(if symbol.kind == SymbolKind::Prop {
+                    "Prop"
+                } else {
+                    "Variant"
+                });
+            }
+        }
+        SymbolKind::Const => {
+            if spec.get_const_by_name(new_name).is_some() {
+                return exists("Constant");
+            }
+        }
+        // refused above
+        SymbolKind::Builtin => {}
+    }
+    for (path, s) in references {
+        edits
+            .entry(path.to_owned())
+            .or_insert_with(Vec::new)
+            .push(TextEdit::new(s.position, new_name.to_owned()));
+    }
+    for file_edits in edits.values_mut() {
+        file_edits.sort_by_key(|e| e.position.start);
+    }
+    Ok(edits)
+}
+
+pub(crate) fn check_name(name: &str) -> Result<(), String> {
+    // kebab case
+    let is_valid = name.chars().next().is_some_and(|c| c.is_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_lowercase() || c.is_numeric() || c == '-')
+        && !name.ends_with('-')
+        && !name.contains("--");
+    if is_valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid name `{}`", name))
+    }
+}
+
+// new name of type should not be taken by other type, builtin type or constant
+pub(crate) fn check_new_type_name(spec: &LeapSpec, new_name: &str) -> Result<(), String> {
+    let exists = |kind: &str| Err(format!("{} `{}` already exists", kind, new_name));
+    if spec.get_type_by_name(new_name).is_some() {
+        return exists("Type");
+    }
+    if is_builtin_name(new_name) {
+        return exists("Builtin type");
+    }
+    if spec.get_const_by_name(new_name).is_some() {
+        return exists("Constant");
+    }
+    Ok(())
+}
+
+// checks that references to type can be renamed, returns enum variants without own name
+// (`page[int]`) which are named by the type, they are renamed together with the type
+pub(crate) fn check_type_references<'a>(
+    spec: &'a LeapSpec,
+    name: &str,
+    new_name: &str,
+) -> Result<Vec<(&'a str, &'a LeapEnum, &'a Prop)>, String> {
+    // type argument with new name would take place of the renamed type
+    for (_, s) in symbols::spec_symbols(spec) {
+        if let Some(owner) = s
+            .owner
+            .filter(|_| s.kind == SymbolKind::TypeRef && s.name == name)
+        {
+            if has_arg(spec.get_type_by_name(owner.get()), new_name) {
+                return Err(format!(
+                    "Type argument `{}` of `{}` conflicts with the new name",
+                    new_name, owner
+                ));
+            }
+        }
+    }
+    let variants: Vec<_> = spec
+        .iter_type_refs()
+        .filter_map(|t| t.as_enum())
+        .flat_map(|e| e.variants.iter().map(move |v| (e.path.as_str(), e, v)))
+        .filter(|(_, _, v)| {
+            matches!(&v.prop_type, ValueType::LeapType { name: n, .. }
+                if n.get() == name && v.name.get() == name && n.position == v.name.position)
+        })
+        .collect();
+    for (_, owner, _) in &variants {
+        if owner.variants.iter().any(|v| v.name.get() == new_name) {
+            return Err(format!("Variant `{}` already exists", new_name));
+        }
+    }
+    Ok(variants)
+}
+
+fn has_arg(leap_type: Option<&LeapType>, name: &str) -> bool {
+    leap_type.is_some_and(|t| t.args().iter().any(|a| a.get() == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+    use crate::textedit::apply_edits;
+
+    fn spec(files: &[(&str, &str)]) -> LeapSpec {
+        let mut definitions = vec![];
+        for (path, data) in files {
+            for mut d in Parser::parse_definitions(data).unwrap() {
+                d.set_path(path.to_string());
+                definitions.push(d);
+            }
+        }
+        LeapSpec::from_definitions(definitions)
+    }
+
+    #[test]
+    fn test_rename() {
+        let a = ".struct user\n    role: role = role.admin\n    friends: list[user]\n";
+        let b = ".enum role\n    admin: user\n    guest: page[user]\n.struct page[t]\n    items: list[t]\n    owner: user\n";
+        let files = [("a.leap", a), ("b.leap", b)];
+        let spec = spec(&files);
+        let renamed = |path: &str, position: usize, name: &str| {
+            rename(&spec, path, position, name).map(|edits| {
+                files
+                    .iter()
+                    .map(|(p, data)| apply_edits(data, edits.get(*p).map_or(&[], |e| e)))
+                    .collect::<Vec<_>>()
+            })
+        };
+        // from reference in other file
+        assert_eq!(
+            renamed("b.leap", 22, "account").unwrap(),
+            vec![
+                ".struct account\n    role: role = role.admin\n    friends: list[account]\n",
+                ".enum role\n    admin: account\n    guest: page[account]\n.struct page[t]\n    items: list[t]\n    owner: account\n",
+            ]
+        );
+        // enum variant with references in default values
+        assert_eq!(
+            renamed("b.leap", 16, "owner").unwrap()[0],
+            ".struct user\n    role: role = role.owner\n    friends: list[user]\n",
+        );
+        // type argument
+        assert_eq!(
+            renamed("b.leap", 62, "item").unwrap()[1],
+            ".enum role\n    admin: user\n    guest: page[user]\n.struct page[item]\n    items: list[item]\n    owner: user\n",
+        );
+        assert_eq!(
+            renamed("a.leap", 9, "page").unwrap_err(),
+            "Type `page` already exists"
+        );
+        assert_eq!(
+            renamed("a.leap", 9, "t").unwrap_err(),
+            "Type argument `t` of `page` conflicts with the new name"
+        );
+        assert_eq!(
+            renamed("a.leap", 9, "int").unwrap_err(),
+            "Builtin type `int` already exists"
+        );
+        assert_eq!(
+            renamed("b.leap", 16, "guest").unwrap_err(),
+            "Variant `guest` already exists"
+        );
+        assert_eq!(
+            renamed("a.leap", 18, "friends").unwrap_err(),
+            "Prop `friends` already exists"
+        );
+        assert_eq!(
+            renamed("a.leap", 55, "seq").unwrap_err(),
+            "Builtin type `list` can't be renamed"
+        );
+        assert_eq!(
+            renamed("a.leap", 9, "User").unwrap_err(),
+            "Invalid name `User`"
+        );
+        assert_eq!(
+            renamed("a.leap", 9, "a--b").unwrap_err(),
+            "Invalid name `a--b`"
+        );
+    }
+
+    #[test]
+    fn test_rename_type_variant() {
+        let a = ".enum e\n    page[int]\n    other: int\n.struct page[t]\n.struct s\n    x: e = e.page\n.const max: int = 1\n";
+        let spec = spec(&[("a.leap", a)]);
+        let renamed = |position: usize, name: &str| {
+            rename(&spec, "a.leap", position, name)
+                .map(|edits| apply_edits(a, edits.get("a.leap").unwrap()))
+        };
+        let expected = ".enum e\n    pg[int]\n    other: int\n.struct pg[t]\n.struct s\n    x: e = e.pg\n.const max: int = 1\n";
+        // variant name is the name of its type
+        assert_eq!(renamed(12, "pg").unwrap(), expected);
+        assert_eq!(renamed(48, "pg").unwrap(), expected);
+        assert_eq!(renamed(80, "pg").unwrap(), expected);
+        assert_eq!(
+            renamed(12, "other").unwrap_err(),
+            "Variant `other` already exists"
+        );
+        assert_eq!(
+            renamed(48, "max").unwrap_err(),
+            "Constant `max` already exists"
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+
+use crate::leaptypes::{LeapConst, LeapSpec, LeapType, Literal, Name, Prop, ValueType};
+use crate::parser::position::Position;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+    // name of struct or enum definition
+    Type,
+    // name of struct or enum used in prop type, constant type or value
+    TypeRef,
+    // type argument, both in definition and in prop type
+    TypeArg,
+    Prop,
+    Variant,
+    // enum variant used in default value or constant value
+    VariantRef,
+    Const,
+    // builtin type used in prop type or constant type (`int`, `list`)
+    Builtin,
+}
+
+// named item of spec, `owner` is name of type containing the item (none for type definitions
+// and constants), for variant references it is name of enum
+#[derive(Debug, Clone, Copy)]
+pub struct Symbol<'a> {
+    pub kind: SymbolKind,
+    pub name: &'a str,
+    // position of the name
+    pub position: Position,
+    pub owner: Option<&'a Name>,
+}
+
+// all symbols of spec with paths of their files, in order of appearance
+pub fn spec_symbols(spec: &LeapSpec) -> Vec<(&str, Symbol<'_>)> {
+    let mut symbols = vec![];
+    for t in spec.iter_type_refs() {
+        let mut type_symbols = vec![];
+        push_type(&mut type_symbols, t);
+        symbols.extend(type_symbols.into_iter().map(|s| (t.path(), s)));
+    }
+    for c in spec.iter_consts() {
+        let mut const_symbols = vec![];
+        push_const(&mut const_symbols, c);
+        symbols.extend(const_symbols.into_iter().map(|s| (c.path.as_str(), s)));
+    }
+    symbols
+}
+
+// symbol which contains position, position right after the name is included too,
+// name of variant without own name (`page[int]`) resolves to the type
+pub fn symbol_at<'a>(spec: &'a LeapSpec, path: &str, position: usize) -> Option<Symbol<'a>> {
+    let mut found = spec_symbols(spec)
+        .into_iter()
+        .filter(|(p, s)| *p == path && s.position.start <= position && position <= s.position.end())
+        .map(|(_, s)| s);
+    let symbol = found.next()?;
+    Some(
+        found
+            .find(|s| s.kind == SymbolKind::TypeRef && s.position == symbol.position)
+            .unwrap_or(symbol),
+    )
+}
+
+// path and position of name where symbol is defined, builtin types have no definition
+pub fn definition<'a>(
+    spec: &'a LeapSpec,
+    path: &'a str,
+    symbol: &Symbol<'a>,
+) -> Option<(&'a str, Position)> {
+    let name = symbol.name;
+    match symbol.kind {
+        SymbolKind::Type | SymbolKind::Prop | SymbolKind::Variant | SymbolKind::Const => {
+            Some((path, symbol.position))
+        }
+        SymbolKind::TypeRef => spec
+            .get_type_by_name(name)
+            .map(|t| (t.path(), t.name().position)),
+        SymbolKind::TypeArg => {
+            let t = spec.get_type_by_name(symbol.owner?.get())?;
+            t.args()
+                .iter()
+                .find(|a| a.get() == name)
+                .map(|a| (t.path(), a.position))
+        }
+        SymbolKind::VariantRef => {
+            let t = spec.get_type_by_name(symbol.owner?.get())?;
+            t.as_enum()?
+                .variants
+                .iter()
+                .find(|v| v.name.get() == name)
+                .map(|v| (t.path(), v.name.position))
+        }
+        SymbolKind::Builtin => None,
+    }
+}
+
+// symbols of spec grouped by their definitions, built once for many lookups of references
+pub struct References<'a> {
+    spec: &'a LeapSpec,
+    by_definition: HashMap<(&'a str, Position), Vec<(&'a str, Symbol<'a>)>>,
+    // builtin types have no definition, they are grouped by name
+    builtins: HashMap<&'a str, Vec<(&'a str, Symbol<'a>)>>,
+}
+
+impl<'a> References<'a> {
+    pub fn new(spec: &'a LeapSpec) -> Self {
+        let mut by_definition: HashMap<_, Vec<_>> = HashMap::new();
+        let mut builtins: HashMap<_, Vec<_>> = HashMap::new();
+        for (path, s) in spec_symbols(spec) {
+            if s.kind == SymbolKind::Builtin {
+                builtins.entry(s.name).or_default().push((path, s));
+            } else if let Some(target) = definition(spec, path, &s) {
+                by_definition.entry(target).or_default().push((path, s));
+            }
+        }
+        References {
+            spec,
+            by_definition,
+            builtins,
+        }
+    }
+
+    // all symbols with same definition as symbol, including the definition,
+    // for builtin type all places where it is used
+    pub fn get(&self, path: &'a str, symbol: &Symbol<'a>) -> &[(&'a str, Symbol<'a>)] {
+        let references = if symbol.kind == SymbolKind::Builtin {
+            self.builtins.get(symbol.name)
+        } else {
+            definition(self.spec, path, symbol).and_then(|t| self.by_definition.get(&t))
+        };
+        references.map_or(&[], Vec::as_slice)
+    }
+}
+
+// every place where type is referenced (prop types, type arguments, constants and enum values)
+pub fn type_references<'a>(spec: &'a LeapSpec, name: &str) -> Vec<(&'a str, Position)> {
+    spec_symbols(spec)
+        .into_iter()
+        .filter(|(_, s)| s.kind == SymbolKind::TypeRef && s.name == name)
+        .map(|(path, s)| (path, s.position))
+        .collect()
+}
+
+fn push<'a>(
+    symbols: &mut Vec<Symbol<'a>>,
+    kind: SymbolKind,
+    name: &'a Name,
+    owner: Option<&'a Name>,
+) {
+    symbols.push(Symbol {
+        kind,
+        name: name.get(),
+        position: name.position,
+        owner,
+    });
+}
+
+fn push_type<'a>(symbols: &mut Vec<Symbol<'a>>, leap_type: &'a LeapType) {
+    let owner = leap_type.name();
+    push(symbols, SymbolKind::Type, owner, None);
+    for a in leap_type.args() {
+        push(symbols, SymbolKind::TypeArg, a, Some(owner));
+    }
+    let (kind, props) = match leap_type {
+        LeapType::Struct(s) => (SymbolKind::Prop, &s.props),
+        LeapType::Enum(e) => (SymbolKind::Variant, &e.variants),
+    };
+    for p in props {
+        push_prop(symbols, kind, p, owner);
+    }
+}
+
+fn push_const<'a>(symbols: &mut Vec<Symbol<'a>>, leap_const: &'a LeapConst) {
+    push(symbols, SymbolKind::Const, &leap_const.name, None);
+    push_value_type(symbols, &leap_const.value_type, None);
+    push_literal(symbols, &leap_const.value, None);
+}
+
+fn push_prop<'a>(symbols: &mut Vec<Symbol<'a>>, kind: SymbolKind, prop: &'a Prop, owner: &'a Name) {
+    push(symbols, kind, &prop.name, Some(owner));
+    push_value_type(symbols, &prop.prop_type, Some(owner));
+    if let Some(default) = &prop.default {
+        push_literal(symbols, default, Some(owner));
+    }
+}
+
+fn push_value_type<'a>(
+    symbols: &mut Vec<Symbol<'a>>,
+    value_type: &'a ValueType,
+    owner: Option<&'a Name>,
+) {
+    match value_type {
+        ValueType::Simple(t, position) => symbols.push(Symbol {
+            kind: SymbolKind::Builtin,
+            name: t.as_str(),
+            position: *position,
+            owner,
+        }),
+        ValueType::List(t, position) => {
+            symbols.push(Symbol {
+                kind: SymbolKind::Builtin,
+                name: "list",
+                // position of `list` without argument
+                position: Position::new(position.start, "list".len()),
+                owner,
+            });
+            push_value_type(symbols, t, owner);
+        }
+        ValueType::TypeArg(name) => push(symbols, SymbolKind::TypeArg, name, owner),
+        ValueType::LeapType { name, args, .. } => {
+            push(symbols, SymbolKind::TypeRef, name, owner);
+            for a in args {
+                push_value_type(symbols, a, owner);
+            }
+        }
+    }
+}
+
+fn push_literal<'a>(symbols: &mut Vec<Symbol<'a>>, literal: &'a Literal, owner: Option<&'a Name>) {
+    if let Literal::EnumVariant { enum_name, variant } = literal {
+        push(symbols, SymbolKind::TypeRef, enum_name, owner);
+        push(symbols, SymbolKind::VariantRef, variant, Some(enum_name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::Parser;
+
+    #[test]
+    fn test_symbols() {
+        let data = ".struct a[t]\n    x: list[b[t]]\n    y: c = c.v\n.enum c\n    v: a[int]\n";
+        let spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        let kinds: Vec<(SymbolKind, &str)> = spec_symbols(&spec)
+            .iter()
+            .map(|(_, s)| (s.kind, s.name))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (SymbolKind::Type, "a"),
+                (SymbolKind::TypeArg, "t"),
+                (SymbolKind::Prop, "x"),
+                (SymbolKind::Builtin, "list"),
+                (SymbolKind::TypeRef, "b"),
+                (SymbolKind::TypeArg, "t"),
+                (SymbolKind::Prop, "y"),
+                (SymbolKind::TypeRef, "c"),
+                (SymbolKind::TypeRef, "c"),
+                (SymbolKind::VariantRef, "v"),
+                (SymbolKind::Type, "c"),
+                (SymbolKind::Variant, "v"),
+                (SymbolKind::TypeRef, "a"),
+                (SymbolKind::Builtin, "int"),
+            ]
+        );
+        let symbol = symbol_at(&spec, "", 26).unwrap();
+        assert_eq!(symbol.kind, SymbolKind::TypeRef);
+        assert_eq!(symbol.name, "b");
+        assert!(symbol_at(&spec, "", 13).is_none());
+        // type argument usage resolves to argument of the struct
+        let symbol = symbol_at(&spec, "", 27).unwrap();
+        let (_, d) = definition(&spec, "", &symbol).unwrap();
+        assert_eq!(d.start, 10);
+        let symbol = symbol_at(&spec, "", 52).unwrap();
+        assert_eq!(symbol.name, "c");
+        let references = References::new(&spec);
+        let positions: Vec<usize> = references
+            .get("", &symbol)
+            .iter()
+            .map(|(_, s)| s.position.start)
+            .collect();
+        assert_eq!(positions, vec![38, 42, 52]);
+        // builtin types have no definition, references are all places where they are used
+        let symbol = symbol_at(&spec, "", 22).unwrap();
+        assert_eq!(symbol.kind, SymbolKind::Builtin);
+        assert_eq!(symbol.name, "list");
+        assert_eq!(symbol.position, Position::new(20, 4));
+        let symbol = symbol_at(&spec, "", 64).unwrap();
+        assert_eq!(symbol.name, "int");
+        assert!(definition(&spec, "", &symbol).is_none());
+        assert_eq!(references.get("", &symbol).len(), 1);
+        assert_eq!(
+            type_references(&spec, "a"),
+            vec![("", Position::new(61, 1))]
+        );
+    }
+}
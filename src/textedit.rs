@@ -0,0 +1,275 @@
+use crate::parser::position::Position;
+
+// replacement of text at position (in characters), empty position means insertion
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextEdit {
+    pub position: Position,
+    pub text: String,
+}
+
+impl TextEdit {
+    pub fn new(position: Position, text: String) -> Self {
+        TextEdit { position, text }
+    }
+
+    pub fn apply(&self, data: &str) -> String {
+        let start = byte_index(data, self.position.start);
+        let end = byte_index(data, self.position.end());
+        let mut result = String::with_capacity(data.len() + self.text.len());
+        result.push_str(&data[..start]);
+        result.push_str(&self.text);
+        result.push_str(&data[end..]);
+        result
+    }
+
+    // change of data length in characters after edit is applied
+    pub fn delta(&self) -> isize {
+        self.text.chars().count() as isize - self.position.length as isize
+    }
+}
+
+// applies not overlapping edits, positions of edits are relative to original data
+pub fn apply_edits(data: &str, edits: &[TextEdit]) -> String {
+    let mut edits: Vec<&TextEdit> = edits.iter().collect();
+    edits.sort_by_key(|e| e.position.start);
+    let mut result = data.to_owned();
+    for edit in edits.into_iter().rev() {
+        result = edit.apply(&result);
+    }
+    result
+}
+
+// line based difference of texts, returned as edits of old text
+pub fn diff(old: &str, new: &str) -> Vec<TextEdit> {
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+    let mut common = vec![];
+    common_lines(&old_lines, &new_lines, (0, 0), &mut common);
+    let mut edits = vec![];
+    let (mut i, mut j, mut offset) = (0, 0, 0);
+    // lines between common lines are replaced, end of texts closes the last edit
+    for (common_i, common_j) in common.into_iter().chain([(n, m)]) {
+        if i < common_i || j < common_j {
+            let length = old_lines[i..common_i]
+                .iter()
+                .map(|l| l.chars().count())
+                .sum();
+            edits.push(TextEdit::new(
+                Position::new(offset, length),
+                new_lines[j..common_j].concat(),
+            ));
+            offset += length;
+        }
+        if common_i < n {
+            offset += old_lines[common_i].chars().count();
+        }
+        i = common_i + 1;
+        j = common_j + 1;
+    }
+    edits
+}
+
+// indices of lines of the longest common subsequence, found with linear space variant of
+// Myers algorithm, common prefix and suffix are skipped before search
+fn common_lines(a: &[&str], b: &[&str], offset: (usize, usize), common: &mut Vec<(usize, usize)>) {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    common.extend((0..prefix).map(|i| (offset.0 + i, offset.1 + i)));
+    let (a, b) = (&a[prefix..], &b[prefix..]);
+    let offset = (offset.0 + prefix, offset.1 + prefix);
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a, b) = (&a[..a.len() - suffix], &b[..b.len() - suffix]);
+    if let Some((x, y)) = middle_split(a, b) {
+        common_lines(&a[..x], &b[..y], offset, common);
+        common_lines(&a[x..], &b[y..], (offset.0 + x, offset.1 + y), common);
+    }
+    common.extend((0..suffix).map(|i| (offset.0 + a.len() + i, offset.1 + b.len() + i)));
+}
+
+// point in the middle of the shortest edit path, where forward and backward searches meet,
+// none if texts have no common lines
+fn middle_split(a: &[&str], b: &[&str]) -> Option<(usize, usize)> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    if n == 0 || m == 0 {
+        return None;
+    }
+    let max_d = (n + m + 1) / 2;
+    let v_offset = max_d + 1;
+    // furthest reached x on each diagonal `k = x - y` (backward search counts from the end)
+    let mut forward = vec![-1; 2 * v_offset as usize + 1];
+    let mut backward = forward.clone();
+    forward[v_offset as usize + 1] = 0;
+    backward[v_offset as usize + 1] = 0;
+    let delta = n - m;
+    // paths meet in forward search if delta is odd, otherwise in backward one
+    let is_front = delta % 2 != 0;
+    // diagonals which went out of texts are not searched further
+    let (mut k1_start, mut k1_end, mut k2_start, mut k2_end) = (0, 0, 0, 0);
+    for d in 0..=max_d {
+        let mut k1 = -d + k1_start;
+        while k1 <= d - k1_end {
+            let k1_offset = (v_offset + k1) as usize;
+            let mut x1 = if k1 == -d || (k1 != d && forward[k1_offset - 1] < forward[k1_offset + 1])
+            {
+                forward[k1_offset + 1]
+            } else {
+                forward[k1_offset - 1] + 1
+            };
+            let mut y1 = x1 - k1;
+            while x1 < n && y1 < m && a[x1 as usize] == b[y1 as usize] {
+                x1 += 1;
+                y1 += 1;
+            }
+            forward[k1_offset] = x1;
+            if x1 > n {
+                k1_end += 2;
+            } else if y1 > m {
+                k1_start += 2;
+            } else if is_front {
+                let k2_offset = v_offset + delta - k1;
+                if 0 <= k2_offset
+                    && k2_offset < backward.len() as isize
+                    && backward[k2_offset as usize] != -1
+                    && x1 >= n - backward[k2_offset as usize]
+                {
+                    return split_point(a, b, x1, y1);
+                }
+            }
+            k1 += 2;
+        }
+        let mut k2 = -d + k2_start;
+        while k2 <= d - k2_end {
+            let k2_offset = (v_offset + k2) as usize;
+            let mut x2 =
+                if k2 == -d || (k2 != d && backward[k2_offset - 1] < backward[k2_offset + 1]) {
+                    backward[k2_offset + 1]
+                } else {
+                    backward[k2_offset - 1] + 1
+                };
+            let mut y2 = x2 - k2;
+            while x2 < n && y2 < m && a[(n - x2 - 1) as usize] == b[(m - y2 - 1) as usize] {
+                x2 += 1;
+                y2 += 1;
+            }
+            backward[k2_offset] = x2;
+            if x2 > n {
+                k2_end += 2;
+            } else if y2 > m {
+                k2_start += 2;
+            } else if !is_front {
+                let k1_offset = v_offset + delta - k2;
+                if 0 <= k1_offset
+                    && k1_offset < forward.len() as isize
+                    && forward[k1_offset as usize] != -1
+                {
+                    let x1 = forward[k1_offset as usize];
+                    let y1 = v_offset + x1 - k1_offset;
+                    if x1 >= n - x2 {
+                        return split_point(a, b, x1, y1);
+                    }
+                }
+            }
+            k2 += 2;
+        }
+    }
+    None
+}
+
+// split point which leaves both parts smaller than texts, otherwise there is nothing to split
+fn split_point(a: &[&str], b: &[&str], x: isize, y: isize) -> Option<(usize, usize)> {
+    let (x, y) = (x as usize, y as usize);
+    if (x, y) == (0, 0) || (x, y) == (a.len(), b.len()) {
+        None
+    } else {
+        Some((x, y))
+    }
+}
+
+// byte index of character, or length of data if index is out of data
+pub(crate) fn byte_index(data: &str, index: usize) -> usize {
+    data.char_indices()
+        .nth(index)
+        .map_or(data.len(), |(i, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let edit = TextEdit::new(Position::new(2, 3), "ыы".to_owned());
+        assert_eq!(edit.apply("абвгде"), "абыые");
+        assert_eq!(edit.delta(), -1);
+        let edits = vec![
+            TextEdit::new(Position::new(4, 0), "!".to_owned()),
+            TextEdit::new(Position::new(0, 1), "".to_owned()),
+        ];
+        assert_eq!(apply_edits("abcd", &edits), "bcd!");
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = "a\nb\nc\nd\n";
+        let new = "a\nx\nc\nd\ne";
+        let edits = diff(old, new);
+        assert_eq!(
+            edits,
+            vec![
+                TextEdit::new(Position::new(2, 2), "x\n".to_owned()),
+                TextEdit::new(Position::new(8, 0), "e".to_owned()),
+            ]
+        );
+        assert_eq!(apply_edits(old, &edits), new);
+        assert!(diff(old, old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_minimal() {
+        // pseudo random texts from few distinct lines
+        let mut seed = 7u32;
+        let mut text = |length: u32| {
+            let mut text = String::new();
+            for _ in 0..length {
+                seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+                text.push(['a', 'b', 'c'][(seed >> 16) as usize % 3]);
+                text.push('\n');
+            }
+            text
+        };
+        for length in 0..40 {
+            let old = text(length % 13);
+            let new = text(length / 3);
+            let edits = diff(&old, &new);
+            assert_eq!(apply_edits(&old, &edits), new);
+            // changed lines count is the same as with quadratic search of common lines
+            let (n, m) = (old.len() / 2, new.len() / 2);
+            let mut common = vec![vec![0; m + 1]; n + 1];
+            for (i, x) in old.lines().enumerate() {
+                for (j, y) in new.lines().enumerate() {
+                    common[i + 1][j + 1] = if x == y {
+                        common[i][j] + 1
+                    } else {
+                        common[i][j + 1].max(common[i + 1][j])
+                    };
+                }
+            }
+            let changed: usize = edits
+                .iter()
+                .map(|e| e.position.length / 2 + e.text.len() / 2)
+                .sum();
+            assert_eq!(changed, n + m - 2 * common[n][m]);
+        }
+        let old: String = (0..100_000).map(|i| format!("{}\n", i)).collect();
+        let new = old.replace("\n500\n", "\nx\n");
+        assert_eq!(
+            diff(&old, &new),
+            vec![TextEdit::new(Position::new(1890, 4), "x\n".to_owned())]
+        );
+    }
+}
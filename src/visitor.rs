@@ -0,0 +1,411 @@
+// traversal of the type model, default methods of `Visitor` and `VisitorMut` walk into all
+// children of the node, overridden method should call `walk_*` function to keep walking
+use crate::leaptypes::{
+    Attribute, LeapConst, LeapDefinition, LeapEnum, LeapSpec, LeapStruct, LeapType, Literal, Name,
+    Prop, ValueType,
+};
+use crate::parser::position::Position;
+
+pub trait Visitor<'a> {
+    fn visit_spec(&mut self, spec: &'a LeapSpec) {
+        walk_spec(self, spec);
+    }
+
+    fn visit_definition(&mut self, definition: &'a LeapDefinition) {
+        walk_definition(self, definition);
+    }
+
+    fn visit_type(&mut self, leap_type: &'a LeapType) {
+        walk_type(self, leap_type);
+    }
+
+    fn visit_struct(&mut self, leap_struct: &'a LeapStruct) {
+        walk_struct(self, leap_struct);
+    }
+
+    fn visit_enum(&mut self, leap_enum: &'a LeapEnum) {
+        walk_enum(self, leap_enum);
+    }
+
+    fn visit_const(&mut self, leap_const: &'a LeapConst) {
+        walk_const(self, leap_const);
+    }
+
+    // props of structs and variants of enums
+    fn visit_prop(&mut self, prop: &'a Prop) {
+        walk_prop(self, prop);
+    }
+
+    fn visit_value_type(&mut self, value_type: &'a ValueType) {
+        walk_value_type(self, value_type);
+    }
+
+    fn visit_literal(&mut self, literal: &'a Literal) {
+        walk_literal(self, literal);
+    }
+
+    fn visit_attribute(&mut self, attribute: &'a Attribute) {
+        self.visit_position(&attribute.position);
+    }
+
+    fn visit_name(&mut self, name: &'a Name) {
+        self.visit_position(&name.position);
+    }
+
+    fn visit_position(&mut self, _position: &'a Position) {}
+}
+
+pub fn walk_spec<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, spec: &'a LeapSpec) {
+    for leap_type in spec.iter_type_refs() {
+        visitor.visit_type(leap_type);
+    }
+    for leap_const in spec.iter_consts() {
+        visitor.visit_const(leap_const);
+    }
+}
+
+pub fn walk_definition<'a, V: Visitor<'a> + ?Sized>(
+    visitor: &mut V,
+    definition: &'a LeapDefinition,
+) {
+    match definition {
+        LeapDefinition::Type(t) => visitor.visit_type(t),
+        LeapDefinition::Const(c) => visitor.visit_const(c),
+    }
+}
+
+pub fn walk_type<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, leap_type: &'a LeapType) {
+    match leap_type {
+        LeapType::Struct(s) => visitor.visit_struct(s),
+        LeapType::Enum(e) => visitor.visit_enum(e),
+    }
+}
+
+pub fn walk_struct<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, leap_struct: &'a LeapStruct) {
+    visitor.visit_position(&leap_struct.position);
+    leap_struct
+        .attributes
+        .iter()
+        .for_each(|a| visitor.visit_attribute(a));
+    visitor.visit_name(&leap_struct.name);
+    leap_struct.args.iter().for_each(|a| visitor.visit_name(a));
+    leap_struct.props.iter().for_each(|p| visitor.visit_prop(p));
+}
+
+pub fn walk_enum<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, leap_enum: &'a LeapEnum) {
+    visitor.visit_position(&leap_enum.position);
+    leap_enum
+        .attributes
+        .iter()
+        .for_each(|a| visitor.visit_attribute(a));
+    visitor.visit_name(&leap_enum.name);
+    leap_enum.args.iter().for_each(|a| visitor.visit_name(a));
+    leap_enum
+        .variants
+        .iter()
+        .for_each(|v| visitor.visit_prop(v));
+}
+
+pub fn walk_const<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, leap_const: &'a LeapConst) {
+    visitor.visit_position(&leap_const.position);
+    leap_const
+        .attributes
+        .iter()
+        .for_each(|a| visitor.visit_attribute(a));
+    visitor.visit_name(&leap_const.name);
+    visitor.visit_value_type(&leap_const.value_type);
+    visitor.visit_literal(&leap_const.value);
+}
+
+pub fn walk_prop<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, prop: &'a Prop) {
+    visitor.visit_position(&prop.position);
+    prop.attributes
+        .iter()
+        .for_each(|a| visitor.visit_attribute(a));
+    visitor.visit_name(&prop.name);
+    visitor.visit_value_type(&prop.prop_type);
+    if let Some(default) = &prop.default {
+        visitor.visit_literal(default);
+    }
+}
+
+pub fn walk_value_type<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, value_type: &'a ValueType) {
+    match value_type {
+        ValueType::Simple(_, position) => visitor.visit_position(position),
+        ValueType::List(t, position) => {
+            visitor.visit_position(position);
+            visitor.visit_value_type(t);
+        }
+        ValueType::TypeArg(name) => visitor.visit_name(name),
+        ValueType::LeapType {
+            name,
+            args,
+            position,
+        } => {
+            visitor.visit_position(position);
+            visitor.visit_name(name);
+            args.iter().for_each(|a| visitor.visit_value_type(a));
+        }
+    }
+}
+
+pub fn walk_literal<'a, V: Visitor<'a> + ?Sized>(visitor: &mut V, literal: &'a Literal) {
+    if let Literal::EnumVariant { enum_name, variant } = literal {
+        visitor.visit_name(enum_name);
+        visitor.visit_name(variant);
+    }
+}
+
+// names of types and constants can be changed, index of spec by name is updated after walk
+pub trait VisitorMut {
+    fn visit_spec_mut(&mut self, spec: &mut LeapSpec) {
+        walk_spec_mut(self, spec);
+    }
+
+    fn visit_definition_mut(&mut self, definition: &mut LeapDefinition) {
+        walk_definition_mut(self, definition);
+    }
+
+    fn visit_type_mut(&mut self, leap_type: &mut LeapType) {
+        walk_type_mut(self, leap_type);
+    }
+
+    fn visit_struct_mut(&mut self, leap_struct: &mut LeapStruct) {
+        walk_struct_mut(self, leap_struct);
+    }
+
+    fn visit_enum_mut(&mut self, leap_enum: &mut LeapEnum) {
+        walk_enum_mut(self, leap_enum);
+    }
+
+    fn visit_const_mut(&mut self, leap_const: &mut LeapConst) {
+        walk_const_mut(self, leap_const);
+    }
+
+    // props of structs and variants of enums
+    fn visit_prop_mut(&mut self, prop: &mut Prop) {
+        walk_prop_mut(self, prop);
+    }
+
+    fn visit_value_type_mut(&mut self, value_type: &mut ValueType) {
+        walk_value_type_mut(self, value_type);
+    }
+
+    fn visit_literal_mut(&mut self, literal: &mut Literal) {
+        walk_literal_mut(self, literal);
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut Attribute) {
+        self.visit_position_mut(&mut attribute.position);
+    }
+
+    fn visit_name_mut(&mut self, name: &mut Name) {
+        self.visit_position_mut(&mut name.position);
+    }
+
+    fn visit_position_mut(&mut self, _position: &mut Position) {}
+}
+
+pub fn walk_spec_mut<V: VisitorMut + ?Sized>(visitor: &mut V, spec: &mut LeapSpec) {
+    for leap_type in spec.types_mut() {
+        visitor.visit_type_mut(leap_type);
+    }
+    for leap_const in spec.consts_mut() {
+        visitor.visit_const_mut(leap_const);
+    }
+    spec.rebuild_index();
+    // props could be retyped
+    spec.mark_recursive_props();
+}
+
+pub fn walk_definition_mut<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    definition: &mut LeapDefinition,
+) {
+    match definition {
+        LeapDefinition::Type(t) => visitor.visit_type_mut(t),
+        LeapDefinition::Const(c) => visitor.visit_const_mut(c),
+    }
+}
+
+pub fn walk_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, leap_type: &mut LeapType) {
+    match leap_type {
+        LeapType::Struct(s) => visitor.visit_struct_mut(s),
+        LeapType::Enum(e) => visitor.visit_enum_mut(e),
+    }
+}
+
+pub fn walk_struct_mut<V: VisitorMut + ?Sized>(visitor: &mut V, leap_struct: &mut LeapStruct) {
+    visitor.visit_position_mut(&mut leap_struct.position);
+    leap_struct
+        .attributes
+        .iter_mut()
+        .for_each(|a| visitor.visit_attribute_mut(a));
+    visitor.visit_name_mut(&mut leap_struct.name);
+    leap_struct
+        .args
+        .iter_mut()
+        .for_each(|a| visitor.visit_name_mut(a));
+    leap_struct
+        .props
+        .iter_mut()
+        .for_each(|p| visitor.visit_prop_mut(p));
+}
+
+pub fn walk_enum_mut<V: VisitorMut + ?Sized>(visitor: &mut V, leap_enum: &mut LeapEnum) {
+    visitor.visit_position_mut(&mut leap_enum.position);
+    leap_enum
+        .attributes
+        .iter_mut()
+        .for_each(|a| visitor.visit_attribute_mut(a));
+    visitor.visit_name_mut(&mut leap_enum.name);
+    leap_enum
+        .args
+        .iter_mut()
+        .for_each(|a| visitor.visit_name_mut(a));
+    leap_enum
+        .variants
+        .iter_mut()
+        .for_each(|v| visitor.visit_prop_mut(v));
+}
+
+pub fn walk_const_mut<V: VisitorMut + ?Sized>(visitor: &mut V, leap_const: &mut LeapConst) {
+    visitor.visit_position_mut(&mut leap_const.position);
+    leap_const
+        .attributes
+        .iter_mut()
+        .for_each(|a| visitor.visit_attribute_mut(a));
+    visitor.visit_name_mut(&mut leap_const.name);
+    visitor.visit_value_type_mut(&mut leap_const.value_type);
+    visitor.visit_literal_mut(&mut leap_const.value);
+}
+
+pub fn walk_prop_mut<V: VisitorMut + ?Sized>(visitor: &mut V, prop: &mut Prop) {
+    visitor.visit_position_mut(&mut prop.position);
+    prop.attributes
+        .iter_mut()
+        .for_each(|a| visitor.visit_attribute_mut(a));
+    visitor.visit_name_mut(&mut prop.name);
+    visitor.visit_value_type_mut(&mut prop.prop_type);
+    if let Some(default) = &mut prop.default {
+        visitor.visit_literal_mut(default);
+    }
+}
+
+pub fn walk_value_type_mut<V: VisitorMut + ?Sized>(visitor: &mut V, value_type: &mut ValueType) {
+    match value_type {
+        ValueType::Simple(_, position) => visitor.visit_position_mut(position),
+        ValueType::List(t, position) => {
+            visitor.visit_position_mut(position);
+            visitor.visit_value_type_mut(t);
+        }
+        ValueType::TypeArg(name) => visitor.visit_name_mut(name),
+        ValueType::LeapType {
+            name,
+            args,
+            position,
+        } => {
+            visitor.visit_position_mut(position);
+            visitor.visit_name_mut(name);
+            args.iter_mut()
+                .for_each(|a| visitor.visit_value_type_mut(a));
+        }
+    }
+}
+
+pub fn walk_literal_mut<V: VisitorMut + ?Sized>(visitor: &mut V, literal: &mut Literal) {
+    if let Literal::EnumVariant { enum_name, variant } = literal {
+        visitor.visit_name_mut(enum_name);
+        visitor.visit_name_mut(variant);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder;
+    use crate::parser::parser::Parser;
+
+    // names of referenced types
+    struct TypeRefs<'a>(Vec<&'a str>);
+
+    impl<'a> Visitor<'a> for TypeRefs<'a> {
+        fn visit_value_type(&mut self, value_type: &'a ValueType) {
+            if let ValueType::LeapType { name, .. } = value_type {
+                self.0.push(name.get());
+            }
+            walk_value_type(self, value_type);
+        }
+    }
+
+    struct Positions(usize);
+
+    impl Visitor<'_> for Positions {
+        fn visit_position(&mut self, _position: &Position) {
+            self.0 += 1;
+        }
+    }
+
+    struct MarkDeprecated;
+
+    impl VisitorMut for MarkDeprecated {
+        fn visit_prop_mut(&mut self, prop: &mut Prop) {
+            if prop.name.get().starts_with("old-") {
+                prop.attributes.push(Attribute {
+                    name: "deprecated".to_owned(),
+                    value: None,
+                    position: prop.position,
+                });
+            }
+            walk_prop_mut(self, prop);
+        }
+    }
+
+    // replaces type of props named `parent`
+    struct Retype;
+
+    impl VisitorMut for Retype {
+        fn visit_prop_mut(&mut self, prop: &mut Prop) {
+            if prop.name.get() == "parent" {
+                prop.prop_type = builder::named("node");
+            }
+        }
+    }
+
+    #[test]
+    fn test_visitor() {
+        let data = ".struct a[t]\n    x: list[b[t]]\n    old-y: c = c.b\n.struct b[t]\n.enum c\n    b[int]\n.const d: c = c.b";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        let mut refs = TypeRefs(vec![]);
+        refs.visit_spec(&spec);
+        assert_eq!(refs.0, vec!["b", "c", "b", "c"]);
+        let mut positions = Positions(0);
+        positions.visit_type(spec.get_type_by_name("a").unwrap());
+        // struct, 2 names, prop `x` (position, name, list, type, name, arg),
+        // prop `old-y` (position, name, type, name, 2 names of value)
+        assert_eq!(positions.0, 15);
+        MarkDeprecated.visit_spec_mut(&mut spec);
+        let a = spec.get_type_by_name("a").unwrap().as_struct().unwrap();
+        assert!(a.props[0].attributes.is_empty());
+        assert_eq!(a.props[1].attributes[0].name, "deprecated");
+    }
+
+    #[test]
+    fn test_visitor_mut_marks_recursive_props() {
+        let data = ".struct node
+    parent: int";
+        let mut spec = LeapSpec::from_definitions(Parser::parse_definitions(data).unwrap());
+        spec.mark_recursive_props();
+        let is_recursive = |spec: &LeapSpec| {
+            spec.get_type_by_name("node")
+                .unwrap()
+                .as_struct()
+                .unwrap()
+                .props[0]
+                .is_recursive
+        };
+        assert!(!is_recursive(&spec));
+        Retype.visit_spec_mut(&mut spec);
+        assert!(is_recursive(&spec));
+    }
+}